@@ -4,18 +4,15 @@ use std::{fs::File, io::Read, path::Path};
 use deeprockgalactic_saveeditor::deep_rock_galactic::{SaveFile};
 
 fn main() {
-    let mut buf = Vec::new();
-
     let path = Path::new("save.sav");
-    let mut file = File::open(&path).expect("Failed to open save file!");
-    file.read_to_end(&mut buf).expect("Failed to read save file!");
+    let file = File::open(path).expect("Failed to open save file!");
 
     let guids_path = Path::new("matrix_cores.json");
-    let mut guids_file = File::open(&guids_path).expect("Failed to open guids!");
+    let mut guids_file = File::open(guids_path).expect("Failed to open guids!");
     let mut guids = String::new();
     guids_file.read_to_string(&mut guids).expect("Failed to read guids!");
 
-    let save_file = SaveFile::new(&mut buf, &guids).expect("Failed to parse save file!");
+    let save_file = SaveFile::from_reader(file, &guids).expect("Failed to parse save file!");
     println!("Minerals: ");
     println!("\tBismor: {}", save_file.minerals.bismor);
     println!("\tEnor: {}", save_file.minerals.enor);
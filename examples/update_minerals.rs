@@ -13,18 +13,14 @@ fn print_minerals(save_file: &SaveFile) {
 }
 
 fn main() {
-    let mut buf = Vec::new();
-
     let path = Path::new("save.sav");
-    let mut file = File::open(&path).expect("Failed to open save file!");
-    file.read_to_end(&mut buf).expect("Failed to read save file!");
 
     let guids_path = Path::new("matrix_cores.json");
-    let mut guids_file = File::open(&guids_path).expect("Failed to open guids!");
+    let mut guids_file = File::open(guids_path).expect("Failed to open guids!");
     let mut guids = String::new();
     guids_file.read_to_string(&mut guids).expect("Failed to read guids!");
 
-    let mut save_file = SaveFile::new(&mut buf, &guids).expect("Failed to parse save file!");
+    let mut save_file = SaveFile::from_path(path, &guids).expect("Failed to parse save file!");
 
     println!("Minerals before modification: ");
     print_minerals(&save_file);
@@ -40,8 +36,8 @@ fn main() {
     println!("Name for file: ");
     io::stdin().read_line(&mut filename).expect("Failed to read filename!");
 
-    let modified_path = Path::new(&filename);
-    let mut modified_file = File::create(&modified_path).expect("Failed to create modified file!");
-    save_file.save(&mut modified_file).expect("Failed to write modified file!");
+    let modified_path = Path::new(filename.trim());
+    let modified_file = File::create(modified_path).expect("Failed to create modified file!");
+    save_file.to_writer(modified_file).expect("Failed to write modified file!");
     println!("Modified file written to: {}", filename);
 }
\ No newline at end of file
@@ -0,0 +1,140 @@
+use std::{cmp::Reverse, fs::{self, File}, io::Write, path::{Path, PathBuf}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use super::{SaveError, SaveFile};
+
+const BACKUP_EXTENSION: &str = "bak";
+const TEMP_EXTENSION: &str = "tmp";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupOptions {
+    // Number of backups kept next to the save, 0 disables backups
+    pub keep: usize
+}
+
+impl Default for BackupOptions {
+    fn default() -> Self {
+        BackupOptions { keep: 5 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub timestamp: SystemTime
+}
+
+fn file_name(path: &Path) -> Result<String, SaveError> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_string())
+        .ok_or_else(|| SaveError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "save path has no file name")))
+}
+
+fn sync_dir(path: &Path) -> Result<(), SaveError> {
+    // directories can't be opened for syncing on windows, the rename is as durable as it gets there
+    if cfg!(unix) {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new(".")
+        };
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+// Writes next to the destination first so the rename never crosses filesystems
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), SaveError> {
+    let temp_path = path.with_file_name(format!("{}.{}", file_name(path)?, TEMP_EXTENSION));
+
+    let result = (|| {
+        let mut temp = File::create(&temp_path)?;
+        temp.write_all(bytes)?;
+        temp.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+
+    sync_dir(path)
+}
+
+fn backup_path(path: &Path, timestamp: SystemTime) -> Result<PathBuf, SaveError> {
+    let millis = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    Ok(path.with_file_name(format!("{}.{}.{}", file_name(path)?, millis, BACKUP_EXTENSION)))
+}
+
+fn create_backup(path: &Path) -> Result<(), SaveError> {
+    let mut timestamp = SystemTime::now();
+    let mut backup = backup_path(path, timestamp)?;
+    while backup.exists() {
+        timestamp += Duration::from_millis(1);
+        backup = backup_path(path, timestamp)?;
+    }
+
+    fs::copy(path, &backup)?;
+    File::open(&backup)?.sync_all()?;
+    Ok(())
+}
+
+// Newest first
+pub fn list_backups<P: AsRef<Path>>(path: P) -> Result<Vec<Backup>, SaveError> {
+    let path = path.as_ref();
+    let prefix = format!("{}.", file_name(path)?);
+    let suffix = format!(".{}", BACKUP_EXTENSION);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new(".")
+    };
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = match entry.file_name().into_string() {
+            Ok(name) => name,
+            Err(_) => continue
+        };
+
+        let millis = name.strip_prefix(&prefix)
+            .and_then(|rest| rest.strip_suffix(&suffix))
+            .and_then(|millis| millis.parse::<u64>().ok());
+        if let Some(millis) = millis {
+            backups.push(Backup {
+                path: entry.path(),
+                timestamp: UNIX_EPOCH + Duration::from_millis(millis)
+            });
+        }
+    }
+
+    backups.sort_by_key(|backup| Reverse(backup.timestamp));
+    Ok(backups)
+}
+
+fn prune_backups(path: &Path, keep: usize) -> Result<(), SaveError> {
+    for backup in list_backups(path)?.iter().skip(keep) {
+        fs::remove_file(&backup.path)?;
+    }
+    Ok(())
+}
+
+pub fn restore_backup<P: AsRef<Path>>(path: P, backup: &Backup) -> Result<(), SaveError> {
+    write_atomic(path.as_ref(), &fs::read(&backup.path)?)
+}
+
+impl SaveFile {
+    pub fn save_to_path<P: AsRef<Path>>(&self, path: P, options: &BackupOptions) -> Result<(), SaveError> {
+        let path = path.as_ref();
+        let bytes = self.to_bytes()?;
+
+        if options.keep > 0 && path.exists() {
+            create_backup(path)?;
+        }
+        write_atomic(path, &bytes)?;
+
+        if options.keep > 0 {
+            prune_backups(path, options.keep)?;
+        }
+        Ok(())
+    }
+}
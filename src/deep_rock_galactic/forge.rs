@@ -0,0 +1,123 @@
+use std::fmt;
+
+use crate::gvas::Guid;
+use super::{Cost, ForgePriorities, MatrixCores, Minerals, OverclockState, SaveError, SaveFile};
+
+pub const FORGE_XP_PER_ITEM: u32 = 1000;
+pub const FORGE_XP_PER_LEVEL: u32 = 5000;
+
+pub(super) fn forged_count(matrix_cores: &MatrixCores) -> u32 {
+    let overclocks = matrix_cores.overclocks.values().filter(|oc| oc.state == OverclockState::Forged).count();
+    let cosmetics = matrix_cores.cosmetics.values().filter(|cosmetic| cosmetic.state == OverclockState::Forged).count();
+    (overclocks + cosmetics) as u32
+}
+
+impl Cost {
+    // What's missing to pay for this, `None` when it's affordable
+    pub fn shortfall(&self, credits: u32, minerals: &Minerals) -> Option<Cost> {
+        let missing = |cost: u32, have: f32| (cost as f32 - have).max(0.0).ceil() as u32;
+        let shortfall = Cost {
+            credits: self.credits.saturating_sub(credits),
+            bismor: missing(self.bismor, minerals.bismor),
+            croppa: missing(self.croppa, minerals.croppa),
+            enor: missing(self.enor, minerals.enor),
+            jadiz: missing(self.jadiz, minerals.jadiz),
+            magnite: missing(self.magnite, minerals.magnite),
+            umanite: missing(self.umanite, minerals.umanite)
+        };
+        if shortfall == Cost::default() {
+            None
+        } else {
+            Some(shortfall)
+        }
+    }
+}
+
+impl fmt::Display for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let amounts = [
+            (self.credits, "credits"), (self.bismor, "bismor"), (self.croppa, "croppa"),
+            (self.enor, "enor"), (self.jadiz, "jadiz"), (self.magnite, "magnite"), (self.umanite, "umanite")
+        ];
+        let amounts: Vec<String> = amounts.iter().filter(|(amount, _)| *amount > 0).map(|(amount, name)| format!("{} {}", amount, name)).collect();
+        f.write_str(&amounts.join(", "))
+    }
+}
+
+impl Minerals {
+    pub(super) fn spend(&mut self, cost: &Cost) {
+        self.bismor -= cost.bismor as f32;
+        self.croppa -= cost.croppa as f32;
+        self.enor -= cost.enor as f32;
+        self.jadiz -= cost.jadiz as f32;
+        self.magnite -= cost.magnite as f32;
+        self.umanite -= cost.umanite as f32;
+    }
+}
+
+impl SaveFile {
+    fn matrix_core(&self, uuid: &str) -> Option<(&Cost, &OverclockState)> {
+        match self.matrix_cores.overclocks.get(uuid) {
+            Some(oc) => Some((&oc.cost, &oc.state)),
+            None => self.matrix_cores.cosmetics.get(uuid).map(|cosmetic| (&cosmetic.cost, &cosmetic.state))
+        }
+    }
+
+    pub(super) fn matrix_core_cost(&self, uuid: &str) -> Result<(Guid, Cost), SaveError> {
+        let guid = Guid::from_hex(uuid).ok_or_else(|| SaveError::InvalidGuid(uuid.to_string()))?;
+        let (cost, state) = self.matrix_core(uuid).ok_or(SaveError::NotInCatalog(guid))?;
+        if *state != OverclockState::Unforged {
+            return Err(SaveError::NotForgeable(uuid.to_string()));
+        }
+        Ok((guid, cost.clone()))
+    }
+
+    // Pays for an unforged overclock or cosmetic and forges it
+    pub fn forge(&mut self, uuid: &str) -> Result<(), SaveError> {
+        let (guid, cost) = self.matrix_core_cost(uuid)?;
+        if let Some(missing) = cost.shortfall(self.credits, &self.minerals) {
+            return Err(SaveError::InsufficientFunds { item: uuid.to_string(), missing });
+        }
+
+        self.credits -= cost.credits;
+        self.minerals.spend(&cost);
+        SaveFile::set_matrix_core_state(&mut self.matrix_cores, &guid, OverclockState::Forged);
+        Ok(())
+    }
+
+    // Forges the plan_forging pick, the most items the credits and minerals cover.
+    // Catalog entries that can't be forged fail the whole call before anything is paid
+    pub fn forge_all_affordable(&mut self) -> Result<Vec<String>, SaveError> {
+        let plan = self.plan_forging(&ForgePriorities::default());
+        for uuid in &plan.items {
+            self.matrix_core_cost(uuid)?;
+        }
+        for uuid in &plan.items {
+            self.forge(uuid)?;
+        }
+        Ok(plan.items)
+    }
+
+    // Items forged or unforged since the XP was loaded or set move it along with them
+    pub fn forge_xp(&self) -> u32 {
+        let delta = (forged_count(&self.matrix_cores) as i64 - self.forge_xp_forged as i64) * FORGE_XP_PER_ITEM as i64;
+        (self.forge_xp as i64 + delta).clamp(0, u32::MAX as i64) as u32
+    }
+
+    pub fn set_forge_xp(&mut self, xp: u32) {
+        self.forge_xp = xp;
+        self.forge_xp_forged = forged_count(&self.matrix_cores);
+    }
+
+    pub fn forge_level(&self) -> u32 {
+        self.forge_xp() / FORGE_XP_PER_LEVEL + 1
+    }
+
+    pub fn set_forge_level(&mut self, level: u32) -> Result<(), SaveError> {
+        if level == 0 || level > u32::MAX / FORGE_XP_PER_LEVEL + 1 {
+            return Err(SaveError::InvalidLevel(level));
+        }
+        self.set_forge_xp((level - 1) * FORGE_XP_PER_LEVEL);
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use crate::gvas::{self, Guid, Property, PropertyValue, StructValue, Value};
+use super::{SaveError, SaveFile};
+
+const MILESTONE_COUNTERS: &str = "MilestoneCounters";
+const ASSIGNMENTS: &str = "Assignments";
+const ACTIVE_ASSIGNMENT: &str = "ActiveAssignment";
+const ASSIGNMENT_ID: &str = "AssignmentID";
+const ASSIGNMENT_PROGRESS: &str = "Progress";
+const ASSIGNMENT_COMPLETED: &str = "bCompleted";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub id: Guid,
+    pub progress: u32,
+    pub completed: bool
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Assignments {
+    pub active: Option<Guid>,
+    pub list: Vec<Assignment>
+}
+
+impl Assignments {
+    pub fn completed(&self) -> impl Iterator<Item = &Assignment> {
+        self.list.iter().filter(|assignment| assignment.completed)
+    }
+
+    pub fn get(&self, id: &Guid) -> Option<&Assignment> {
+        self.list.iter().find(|assignment| assignment.id == *id)
+    }
+}
+
+// Counters are keyed by the names the game uses, like "EnemiesKilled"
+pub(super) fn load_milestones(properties: &[Property]) -> Result<HashMap<String, u32>, SaveError> {
+    match gvas::find_property(properties, MILESTONE_COUNTERS) {
+        Some(e) => e.value.map_entries()
+            .and_then(|entries| entries.iter().map(|(k, v)| Some((k.as_str()?.to_string(), v.as_int()? as u32))).collect())
+            .ok_or_else(|| SaveError::unexpected_type(MILESTONE_COUNTERS, "a map of names to ints")),
+        None => Ok(HashMap::new())
+    }
+}
+
+pub(super) fn save_milestones(milestones: &HashMap<String, u32>, properties: &mut Vec<Property>) -> Result<(), SaveError> {
+    let mut counters: Vec<_> = milestones.iter().collect();
+    counters.sort();
+    gvas::upsert_map(properties, MILESTONE_COUNTERS, ("NameProperty", "IntProperty"), &counters,
+        |k| k.as_str().map(str::to_string),
+        |(name, count)| (Value::Str(name.to_string()), Value::Int(**count as i32)),
+        |value, (_, count)| {
+            *value = Value::Int(**count as i32);
+            Ok(())
+        })
+}
+
+fn get_assignment(element: &StructValue) -> Option<Assignment> {
+    let element = element.properties()?;
+    let id = element.iter().find(|p| p.name == ASSIGNMENT_ID)?.value.as_guid()?;
+    let progress = element.iter().find(|p| p.name == ASSIGNMENT_PROGRESS)?.value.as_int()?;
+    let completed = match element.iter().find(|p| p.name == ASSIGNMENT_COMPLETED) {
+        Some(e) => e.value.as_bool()?,
+        None => false
+    };
+    Some(Assignment { id, progress: progress as u32, completed })
+}
+
+fn assignment_id(element: &[Property]) -> Option<Guid> {
+    element.iter().find(|p| p.name == ASSIGNMENT_ID)?.value.as_guid()
+}
+
+impl Assignments {
+    pub(super) fn load(properties: &[Property]) -> Result<Assignments, SaveError> {
+        let active = match gvas::find_property(properties, ACTIVE_ASSIGNMENT) {
+            Some(e) => Some(e.value.as_guid().ok_or_else(|| SaveError::unexpected_type(ACTIVE_ASSIGNMENT, "a guid"))?),
+            None => None
+        };
+        let list = match gvas::find_property(properties, ASSIGNMENTS) {
+            Some(e) => e.value.as_struct_array()
+                .and_then(|elements| elements.iter().map(get_assignment).collect())
+                .ok_or_else(|| SaveError::unexpected_type(ASSIGNMENTS, "an array of assignments"))?,
+            None => Vec::new()
+        };
+        Ok(Assignments { active, list })
+    }
+
+    pub(super) fn save(&self, properties: &mut Vec<Property>) -> Result<(), SaveError> {
+        match (gvas::find_property_mut(properties, ACTIVE_ASSIGNMENT), self.active) {
+            (Some(e), Some(active)) => e.value = PropertyValue::guid(active),
+            (Some(_), None) => {
+                let list = gvas::find_property_list_mut(properties, &|list| list.iter().any(|p| p.name == ACTIVE_ASSIGNMENT))
+                    .ok_or_else(|| SaveError::missing(ACTIVE_ASSIGNMENT))?;
+                list.retain(|p| p.name != ACTIVE_ASSIGNMENT);
+            },
+            (None, Some(active)) => properties.push(Property::new(ACTIVE_ASSIGNMENT, PropertyValue::guid(active))),
+            (None, None) => {}
+        }
+
+        gvas::upsert_struct_array(properties, ASSIGNMENTS, "AssignmentSave", &self.list, assignment_id,
+            |assignment| vec![Property::new(ASSIGNMENT_ID, PropertyValue::guid(assignment.id))],
+            |element, assignment| {
+                gvas::set_property(element, ASSIGNMENT_PROGRESS, PropertyValue::Int(assignment.progress as i32));
+                // a missing flag reads as not completed
+                if assignment.completed || element.iter().any(|p| p.name == ASSIGNMENT_COMPLETED) {
+                    gvas::set_property(element, ASSIGNMENT_COMPLETED, PropertyValue::Bool(assignment.completed));
+                }
+                Ok(())
+            })
+    }
+}
+
+impl SaveFile {
+    pub fn milestone(&self, name: &str) -> u32 {
+        self.milestones.get(name).copied().unwrap_or(0)
+    }
+}
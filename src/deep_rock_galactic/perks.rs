@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+use crate::gvas::{self, Guid, Property, PropertyValue, StructValue};
+use super::{DwarfClass, SaveError, SaveFile};
+
+const UNLOCKED_PERKS: &str = "UnlockedPerks";
+const PERK_ID: &str = "PerkID";
+const PERK_TIER: &str = "Tier";
+const EQUIPPED_ACTIVE_PERKS: &str = "EquippedActivePerks";
+const EQUIPPED_PASSIVE_PERKS: &str = "EquippedPassivePerks";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerkKind {
+    Active,
+    Passive
+}
+
+// Same layout as matrix_cores.json, perks keyed by their guid
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Perk {
+    pub name: String,
+    pub kind: PerkKind,
+    pub tier_costs: Vec<u32>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PerkCatalog {
+    pub perks: HashMap<String, Perk>
+}
+
+impl PerkCatalog {
+    pub fn from_json(json: &str) -> Result<Self, SaveError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn get(&self, guid: &Guid) -> Option<&Perk> {
+        self.perks.get(&guid.to_hex())
+    }
+
+    fn get_or_err(&self, guid: &Guid) -> Result<&Perk, SaveError> {
+        self.get(guid).ok_or(SaveError::NotInCatalog(*guid))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnlockedPerk {
+    pub perk: Guid,
+    pub tier: u32
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EquippedPerks {
+    pub active: Vec<Guid>,
+    pub passive: Vec<Guid>
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Perks {
+    pub unlocked: Vec<UnlockedPerk>,
+    pub equipped: HashMap<DwarfClass, EquippedPerks>
+}
+
+fn get_unlocked(properties: &[Property]) -> Result<Vec<UnlockedPerk>, SaveError> {
+    let unlocked = match gvas::find_property(properties, UNLOCKED_PERKS) {
+        Some(e) => e,
+        None => return Ok(Vec::new())
+    };
+
+    unlocked.value.as_struct_array()
+        .and_then(|elements| elements.iter().map(|element| {
+            let element = element.properties()?;
+            let perk = element.iter().find(|p| p.name == PERK_ID)?.value.as_guid()?;
+            let tier = element.iter().find(|p| p.name == PERK_TIER)?.value.as_int()?;
+            Some(UnlockedPerk { perk, tier: tier as u32 })
+        }).collect())
+        .ok_or_else(|| SaveError::unexpected_type(UNLOCKED_PERKS, "an array of perk unlocks"))
+}
+
+fn get_equipped(properties: &[Property], class: DwarfClass) -> Result<EquippedPerks, SaveError> {
+    let class_properties = gvas::find_property_list(properties, &|list| SaveFile::is_class_properties(list, class.id()))
+        .ok_or_else(|| SaveError::missing(&format!("{} XP", class)))?;
+
+    let get_slots = |name: &str| match class_properties.iter().find(|p| p.name == name) {
+        Some(e) => SaveFile::get_guid_array(e),
+        None => Ok(Vec::new())
+    };
+    Ok(EquippedPerks { active: get_slots(EQUIPPED_ACTIVE_PERKS)?, passive: get_slots(EQUIPPED_PASSIVE_PERKS)? })
+}
+
+// Class lists without the slots get them added after the class XP
+fn set_guid_slots(class_properties: &mut Vec<Property>, name: &str, guids: &[Guid]) -> Result<(), SaveError> {
+    match class_properties.iter_mut().find(|p| p.name == name) {
+        Some(e) => *e.value.as_struct_array_mut().ok_or_else(|| SaveError::unexpected_type(name, "an array of guids"))? =
+            guids.iter().map(|guid| StructValue::Guid(*guid)).collect(),
+        None if !guids.is_empty() => {
+            let pos = class_properties.iter().position(|p| p.name == super::XP).map_or(class_properties.len(), |pos| pos + 1);
+            class_properties.insert(pos, Property::new(name, PropertyValue::guid_array(name, guids)));
+        },
+        None => {}
+    }
+    Ok(())
+}
+
+impl Perks {
+    pub(super) fn load(properties: &[Property]) -> Result<Perks, SaveError> {
+        let unlocked = get_unlocked(properties)?;
+        let equipped = DwarfClass::ALL.iter()
+            .map(|class| Ok((*class, get_equipped(properties, *class)?)))
+            .collect::<Result<HashMap<_, _>, SaveError>>()?;
+        Ok(Perks { unlocked, equipped })
+    }
+
+    pub(super) fn save(&self, properties: &mut Vec<Property>) -> Result<(), SaveError> {
+        let elements: Vec<StructValue> = self.unlocked.iter().map(|unlocked| StructValue::Properties(vec![
+            Property::new(PERK_ID, PropertyValue::guid(unlocked.perk)),
+            Property::new(PERK_TIER, PropertyValue::Int(unlocked.tier as i32))
+        ])).collect();
+
+        match gvas::find_property_mut(properties, UNLOCKED_PERKS) {
+            Some(e) => *e.value.as_struct_array_mut().ok_or_else(|| SaveError::unexpected_type(UNLOCKED_PERKS, "an array of perk unlocks"))? = elements,
+            None if !elements.is_empty() => properties.push(Property::new(UNLOCKED_PERKS, PropertyValue::struct_array(UNLOCKED_PERKS, "PerkUnlock", elements))),
+            None => {}
+        }
+
+        for (class, equipped) in &self.equipped {
+            let class_properties = gvas::find_property_list_mut(properties, &|list| SaveFile::is_class_properties(list, class.id()))
+                .ok_or_else(|| SaveError::missing(&format!("{} XP", class)))?;
+            set_guid_slots(class_properties, EQUIPPED_ACTIVE_PERKS, &equipped.active)?;
+            set_guid_slots(class_properties, EQUIPPED_PASSIVE_PERKS, &equipped.passive)?;
+        }
+        Ok(())
+    }
+
+    pub fn tier(&self, perk: &Guid) -> u32 {
+        self.unlocked.iter().find(|unlocked| unlocked.perk == *perk).map_or(0, |unlocked| unlocked.tier)
+    }
+
+    // Perk points that went into the unlocked tiers
+    pub fn points_spent(&self, catalog: &PerkCatalog) -> Result<u32, SaveError> {
+        let overflow = || SaveError::Overflow("perk points spent");
+        self.unlocked.iter().try_fold(0u32, |spent, unlocked| {
+            let perk = catalog.get_or_err(&unlocked.perk)?;
+            let tiers = perk.tier_costs.iter().take(unlocked.tier as usize).try_fold(0u32, |sum, cost| sum.checked_add(*cost)).ok_or_else(overflow)?;
+            spent.checked_add(tiers).ok_or_else(overflow)
+        })
+    }
+
+    // Equipped perks that aren't unlocked, or sit in a slot of the wrong kind
+    pub fn invalid_equipped(&self, catalog: &PerkCatalog) -> Vec<(DwarfClass, Guid)> {
+        let mut invalid: Vec<(DwarfClass, Guid)> = self.equipped.iter().flat_map(|(class, equipped)| {
+            let active = equipped.active.iter().map(|guid| (PerkKind::Active, guid));
+            let passive = equipped.passive.iter().map(|guid| (PerkKind::Passive, guid));
+            active.chain(passive)
+                .filter(|(kind, guid)| self.tier(guid) == 0 || catalog.get(guid).is_none_or(|perk| perk.kind != *kind))
+                .map(|(_, guid)| (*class, *guid))
+                .collect::<Vec<_>>()
+        }).collect();
+        invalid.sort();
+        invalid
+    }
+}
+
+impl SaveFile {
+    // Locks every perk again and hands the points back
+    pub fn reset_perks(&mut self, catalog: &PerkCatalog) -> Result<u32, SaveError> {
+        let refund = self.perks.points_spent(catalog)?;
+        let perkpoints = self.perkpoints.checked_add(refund).ok_or(SaveError::Overflow("perk points"))?;
+        self.perks.unlocked.clear();
+        for equipped in self.perks.equipped.values_mut() {
+            *equipped = EquippedPerks::default();
+        }
+        self.perkpoints = perkpoints;
+        Ok(refund)
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use super::{Cost, DwarfClass, Minerals, OverclockState, SaveFile};
+
+// Credits followed by the six minerals, in Cost field order
+const DIMENSIONS: usize = 7;
+// Past this the best plan found so far is returned and marked as not proven optimal
+const NODE_BUDGET: usize = 200_000;
+
+// Weapon weights win over class weights, anything not listed counts as 1 and a weight of 0 skips the item
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ForgePriorities {
+    pub classes: HashMap<DwarfClass, u32>,
+    pub weapons: HashMap<String, u32>
+}
+
+impl ForgePriorities {
+    fn weight(&self, class: DwarfClass, weapon: Option<&str>) -> u32 {
+        weapon.and_then(|weapon| self.weapons.get(weapon))
+            .or_else(|| self.classes.get(&class))
+            .copied()
+            .unwrap_or(1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForgePlan {
+    pub items: Vec<String>,
+    pub score: u32,
+    pub cost: Cost,
+    pub leftover_credits: u32,
+    pub leftover_minerals: Minerals,
+    pub optimal: bool
+}
+
+struct Candidate {
+    uuid: String,
+    weight: u64,
+    cost: [u64; DIMENSIONS]
+}
+
+fn amounts(cost: &Cost) -> [u64; DIMENSIONS] {
+    [cost.credits, cost.bismor, cost.croppa, cost.enor, cost.jadiz, cost.magnite, cost.umanite].map(u64::from)
+}
+
+fn fits(cost: &[u64; DIMENSIONS], remaining: &[u64; DIMENSIONS]) -> bool {
+    cost.iter().zip(remaining).all(|(cost, remaining)| cost <= remaining)
+}
+
+fn subtract(remaining: &[u64; DIMENSIONS], cost: &[u64; DIMENSIONS]) -> [u64; DIMENSIONS] {
+    let mut left = *remaining;
+    for (left, cost) in left.iter_mut().zip(cost) {
+        *left -= cost;
+    }
+    left
+}
+
+struct Search<'a> {
+    items: &'a [Candidate],
+    // Per resource, item indices from the best to the worst weight per unit of that resource
+    by_ratio: Vec<Vec<usize>>,
+    current: Vec<bool>,
+    best: Vec<bool>,
+    best_score: u64,
+    nodes: usize
+}
+
+impl<'a> Search<'a> {
+    fn new(items: &'a [Candidate]) -> Search<'a> {
+        let by_ratio = (0..DIMENSIONS).map(|d| {
+            let mut order: Vec<usize> = (0..items.len()).collect();
+            // weight / cost compared as cross products, free items first
+            order.sort_by(|a, b| {
+                let (a, b) = (&items[*a], &items[*b]);
+                (b.weight * a.cost[d]).cmp(&(a.weight * b.cost[d]))
+            });
+            order
+        }).collect();
+
+        Search { items, by_ratio, current: vec![false; items.len()], best: vec![false; items.len()], best_score: 0, nodes: 0 }
+    }
+
+    // Fractional knapsack on each resource on its own, the tightest one bounds the subtree
+    fn bound(&self, next: usize, score: u64, remaining: &[u64; DIMENSIONS]) -> f64 {
+        (0..DIMENSIONS).map(|d| {
+            let mut capacity = remaining[d] as f64;
+            let mut bound = score as f64;
+            for item in self.by_ratio[d].iter().filter(|i| **i >= next).map(|i| &self.items[*i]) {
+                let cost = item.cost[d] as f64;
+                if cost <= capacity {
+                    capacity -= cost;
+                    bound += item.weight as f64;
+                } else {
+                    bound += item.weight as f64 * capacity / cost;
+                    break;
+                }
+            }
+            bound
+        }).fold(f64::MAX, f64::min)
+    }
+
+    fn greedy(&mut self, budget: &[u64; DIMENSIONS]) {
+        let mut remaining = *budget;
+        for (i, item) in self.items.iter().enumerate() {
+            if fits(&item.cost, &remaining) {
+                remaining = subtract(&remaining, &item.cost);
+                self.best[i] = true;
+                self.best_score += item.weight;
+            }
+        }
+    }
+
+    // Returns false when the node budget ran out
+    fn search(&mut self, next: usize, score: u64, remaining: &[u64; DIMENSIONS]) -> bool {
+        self.nodes += 1;
+        if self.nodes > NODE_BUDGET {
+            return false;
+        }
+        if score > self.best_score {
+            self.best_score = score;
+            self.best = self.current.clone();
+        }
+        if next == self.items.len() || self.bound(next, score, remaining).floor() as u64 <= self.best_score {
+            return true;
+        }
+
+        let item = &self.items[next];
+        if fits(&item.cost, remaining) {
+            self.current[next] = true;
+            let finished = self.search(next + 1, score + item.weight, &subtract(remaining, &item.cost));
+            self.current[next] = false;
+            if !finished {
+                return false;
+            }
+        }
+        self.search(next + 1, score, remaining)
+    }
+}
+
+impl SaveFile {
+    // Which unforged items to forge with the credits and minerals at hand for the highest total weight
+    pub fn plan_forging(&self, priorities: &ForgePriorities) -> ForgePlan {
+        let minerals = &self.minerals;
+        let [bismor, croppa, enor, jadiz, magnite, umanite] = [
+            minerals.bismor, minerals.croppa, minerals.enor, minerals.jadiz, minerals.magnite, minerals.umanite
+        ].map(|amount| amount.max(0.0).floor() as u64);
+        // credits stay integers, past 2^24 they wouldn't survive a round trip through f32
+        let budget = [u64::from(self.credits), bismor, croppa, enor, jadiz, magnite, umanite];
+
+        let overclocks = self.matrix_cores.overclocks.iter()
+            .map(|(uuid, oc)| (uuid, &oc.cost, &oc.state, priorities.weight(oc.class, Some(&oc.weapon))));
+        let cosmetics = self.matrix_cores.cosmetics.iter()
+            .map(|(uuid, cosmetic)| (uuid, &cosmetic.cost, &cosmetic.state, priorities.weight(cosmetic.class, None)));
+        let mut items: Vec<Candidate> = overclocks.chain(cosmetics)
+            .filter(|(_, _, state, weight)| **state == OverclockState::Unforged && *weight > 0)
+            .map(|(uuid, cost, _, weight)| Candidate { uuid: uuid.clone(), weight: weight as u64, cost: amounts(cost) })
+            .filter(|item| fits(&item.cost, &budget))
+            .collect();
+
+        // Best weight for the share of the budget it takes up first, that's also the greedy order
+        let share = |item: &Candidate| item.cost.iter().zip(&budget)
+            .map(|(cost, budget)| if *budget == 0 { 0.0 } else { *cost as f64 / *budget as f64 })
+            .sum::<f64>();
+        items.sort_by(|a, b| {
+            let (a_ratio, b_ratio) = (a.weight as f64 / share(a).max(f64::EPSILON), b.weight as f64 / share(b).max(f64::EPSILON));
+            b_ratio.total_cmp(&a_ratio).then_with(|| a.uuid.cmp(&b.uuid))
+        });
+
+        let mut search = Search::new(&items);
+        search.greedy(&budget);
+        let optimal = search.search(0, 0, &budget);
+
+        let chosen: Vec<&Candidate> = items.iter().zip(&search.best).filter(|(_, chosen)| **chosen).map(|(item, _)| item).collect();
+        let spent = chosen.iter().fold([0u64; DIMENSIONS], |mut spent, item| {
+            for (spent, cost) in spent.iter_mut().zip(&item.cost) {
+                *spent += cost;
+            }
+            spent
+        });
+        let cost = Cost {
+            credits: spent[0] as u32,
+            bismor: spent[1] as u32,
+            croppa: spent[2] as u32,
+            enor: spent[3] as u32,
+            jadiz: spent[4] as u32,
+            magnite: spent[5] as u32,
+            umanite: spent[6] as u32
+        };
+        let mut leftover_minerals = self.minerals.clone();
+        leftover_minerals.spend(&cost);
+
+        let mut plan_items: Vec<String> = chosen.iter().map(|item| item.uuid.clone()).collect();
+        plan_items.sort();
+        ForgePlan {
+            items: plan_items,
+            score: search.best_score as u32,
+            leftover_credits: self.credits - cost.credits,
+            cost,
+            leftover_minerals,
+            optimal
+        }
+    }
+}
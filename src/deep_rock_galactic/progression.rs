@@ -0,0 +1,82 @@
+use super::{ClassProgress, DwarfClass, SaveError, SaveFile};
+
+pub const MAX_LEVEL: u32 = 25;
+pub const PROMOTION_PERK_POINTS: u32 = 3;
+
+const PROMOTION_TIERS: [&str; 6] = ["Bronze", "Silver", "Gold", "Platinum", "Emerald", "Legendary"];
+const TIER_NUMERALS: [&str; 3] = ["I", "II", "III"];
+
+// XP needed to reach each level, starting at level 1
+const XP_TABLE: [u32; MAX_LEVEL as usize] = [
+    0, 3000, 7000, 12000, 18000, 25000, 33000, 42000, 52000, 63000,
+    75000, 88000, 102000, 117000, 132500, 148500, 165000, 182000, 199500, 217500,
+    236000, 255000, 274500, 294500, 315000
+];
+
+pub fn xp_for_level(level: u32) -> Result<u32, SaveError> {
+    match level {
+        1..=MAX_LEVEL => Ok(XP_TABLE[level as usize - 1]),
+        _ => Err(SaveError::InvalidLevel(level))
+    }
+}
+
+// Every three promotions move up a tier, anything past Legendary III stays there
+pub fn promotion_title(promotions: u32) -> Option<String> {
+    let step = promotions.checked_sub(1)?.min(PROMOTION_TIERS.len() as u32 * 3 - 1) as usize;
+    Some(format!("{} {}", PROMOTION_TIERS[step / 3], TIER_NUMERALS[step % 3]))
+}
+
+impl ClassProgress {
+    pub fn level(&self) -> u32 {
+        XP_TABLE.iter().take_while(|xp| **xp <= self.xp).count() as u32
+    }
+
+    pub fn xp_into_level(&self) -> u32 {
+        self.xp - XP_TABLE[self.level() as usize - 1]
+    }
+
+    pub fn set_level(&mut self, level: u32) -> Result<(), SaveError> {
+        self.xp = xp_for_level(level)?;
+        Ok(())
+    }
+
+    pub fn promotion_title(&self) -> Option<String> {
+        promotion_title(self.promotions)
+    }
+
+    // Levels gained so far, a promotion counts for a full run of levels
+    fn levels_gained(&self) -> u32 {
+        self.promotions.saturating_mul(MAX_LEVEL).saturating_add(self.level() - 1)
+    }
+}
+
+impl SaveFile {
+    pub fn set_level(&mut self, class: DwarfClass, level: u32) -> Result<(), SaveError> {
+        self.classes.entry(class).or_default().set_level(level)
+    }
+
+    // Promoting starts the class over at level 1
+    pub fn promote(&mut self, class: DwarfClass) -> Result<(), SaveError> {
+        let progress = self.classes.entry(class).or_default();
+        let level = progress.level();
+        if level < MAX_LEVEL {
+            return Err(SaveError::CannotPromote { class: class.to_string(), level });
+        }
+
+        let promotions = progress.promotions.checked_add(1).ok_or(SaveError::Overflow("promotions"))?;
+        let perkpoints = self.perkpoints.checked_add(PROMOTION_PERK_POINTS).ok_or(SaveError::Overflow("perk points"))?;
+        progress.xp = 0;
+        progress.promotions = promotions;
+        self.perkpoints = perkpoints;
+        Ok(())
+    }
+
+    pub fn player_rank(&self) -> u32 {
+        self.classes.values().map(ClassProgress::levels_gained).fold(1, u32::saturating_add)
+    }
+
+    // The highest promotion tier across all classes
+    pub fn player_title(&self) -> Option<String> {
+        promotion_title(self.classes.values().map(|progress| progress.promotions).max()?)
+    }
+}
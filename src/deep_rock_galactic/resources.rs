@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use crate::gvas::Guid;
+use super::{SaveError, SaveFile};
+
+//Minerals
+pub(super) const      BISMOR: [u8; 16] = [0xAF, 0x0D, 0xC4, 0xFE, 0x83, 0x61, 0xBB, 0x48, 0xB3, 0x2C, 0x92, 0xCC, 0x97, 0xE2, 0x1D, 0xE7];
+pub(super) const        ENOR: [u8; 16] = [0x48, 0x8D, 0x05, 0x14, 0x6F, 0x5F, 0x75, 0x4B, 0xA3, 0xD4, 0x61, 0x0D, 0x08, 0xC0, 0x60, 0x3E];
+pub(super) const       JADIZ: [u8; 16] = [0x22, 0xBC, 0x4F, 0x7D, 0x07, 0xD1, 0x3E, 0x43, 0xBF, 0xCA, 0x81, 0xBD, 0x9C, 0x14, 0xB1, 0xAF];
+pub(super) const      CROPPA: [u8; 16] = [0x8A, 0xA7, 0xFB, 0x43, 0x29, 0x3A, 0x0B, 0x49, 0xB8, 0xBE, 0x42, 0xFF, 0xE0, 0x68, 0xA4, 0x4C];
+pub(super) const     MAGNITE: [u8; 16] = [0xAA, 0xDE, 0xD8, 0x76, 0x6C, 0x22, 0x7D, 0x40, 0x80, 0x32, 0xAF, 0xD1, 0x8D, 0x63, 0x56, 0x1E];
+pub(super) const     UMANITE: [u8; 16] = [0x5F, 0x2B, 0xCF, 0x83, 0x47, 0x76, 0x0A, 0x42, 0xA2, 0x3B, 0x6E, 0xDC, 0x07, 0xC0, 0x94, 0x1D];
+
+//Brewing
+pub(super) const       YEAST: [u8; 16] = [0x07, 0x85, 0x48, 0xB9, 0x32, 0x32, 0xC0, 0x40, 0x85, 0xF8, 0x92, 0xE0, 0x84, 0xA7, 0x41, 0x00];
+pub(super) const      STARCH: [u8; 16] = [0x72, 0x31, 0x22, 0x04, 0xE2, 0x87, 0xBC, 0x41, 0x81, 0x55, 0x40, 0xA0, 0xCF, 0x88, 0x12, 0x80];
+pub(super) const      BARLEY: [u8; 16] = [0x22, 0xDA, 0xA7, 0x57, 0xAD, 0x7A, 0x80, 0x49, 0x89, 0x1B, 0x17, 0xED, 0xCC, 0x2F, 0xE0, 0x98];
+pub(super) const        MALT: [u8; 16] = [0x41, 0xEA, 0x55, 0x0C, 0x1D, 0x46, 0xC5, 0x4B, 0xBE, 0x2E, 0x9C, 0xA5, 0xA7, 0xAC, 0xCB, 0x06];
+
+// Misc?
+pub(super) const ERROR_CORES: [u8; 16] = [0x58, 0x28, 0x65, 0x2C, 0x9A, 0x5D, 0xE8, 0x45, 0xA9, 0xE2, 0xE1, 0xB8, 0xB4, 0x63, 0xC5, 0x16];
+pub(super) const BLANK_CORES: [u8; 16] = [0xA1, 0x0C, 0xB2, 0x85, 0x38, 0x71, 0xFB, 0x49, 0x9A, 0xC8, 0x54, 0xA1, 0xCD, 0xE2, 0x20, 0x2C];
+
+const KNOWN_RESOURCES: [([u8; 16], &str); 12] = [
+    (BISMOR, "Bismor"),
+    (ENOR, "Enor Pearl"),
+    (JADIZ, "Jadiz"),
+    (CROPPA, "Croppa"),
+    (MAGNITE, "Magnite"),
+    (UMANITE, "Umanite"),
+    (YEAST, "Yeast Cone"),
+    (STARCH, "Starch Nut"),
+    (BARLEY, "Barley Bulb"),
+    (MALT, "Malt Star"),
+    (ERROR_CORES, "Error Cube"),
+    (BLANK_CORES, "Blank Matrix Core")
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceRegistry {
+    names: HashMap<Guid, String>
+}
+
+impl Default for ResourceRegistry {
+    fn default() -> Self {
+        ResourceRegistry {
+            names: KNOWN_RESOURCES.iter().map(|(guid, name)| (Guid(*guid), name.to_string())).collect()
+        }
+    }
+}
+
+impl ResourceRegistry {
+    // Extra names as a {"GUID": "Name"} object, same guid format as matrix_cores.json
+    pub fn extend_from_json(&mut self, json: &str) -> Result<(), SaveError> {
+        for (uuid, name) in serde_json::from_str::<HashMap<String, String>>(json)? {
+            let guid = Guid::from_hex(&uuid).ok_or(SaveError::InvalidGuid(uuid))?;
+            self.register(guid, &name);
+        }
+        Ok(())
+    }
+
+    pub fn register(&mut self, guid: Guid, name: &str) {
+        self.names.insert(guid, name.to_string());
+    }
+
+    pub fn name(&self, guid: &Guid) -> Option<&str> {
+        self.names.get(guid).map(|name| name.as_str())
+    }
+
+    pub fn guid(&self, name: &str) -> Option<Guid> {
+        self.names.iter().find(|(_, n)| n.eq_ignore_ascii_case(name)).map(|(guid, _)| *guid)
+    }
+}
+
+pub(super) fn has_typed_field(guid: &Guid) -> bool {
+    KNOWN_RESOURCES.iter().any(|(known, _)| Guid(*known) == *guid)
+}
+
+// The save's OwnedResources entries without a typed SaveFile field, minerals, brewing
+// ingredients and cores only live in theirs. SaveFile::resource covers all of them by name.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Resources {
+    pub owned: HashMap<Guid, f32>,
+    pub registry: ResourceRegistry
+}
+
+impl Resources {
+    fn untyped_guid(&self, name: &str) -> Result<Guid, SaveError> {
+        let guid = self.registry.guid(name).ok_or_else(|| SaveError::UnknownResource(name.to_string()))?;
+        if has_typed_field(&guid) {
+            return Err(SaveError::TypedResource(name.to_string()));
+        }
+        Ok(guid)
+    }
+
+    // Registered resources the save has no entry for yet count as zero
+    pub fn get(&self, name: &str) -> Result<f32, SaveError> {
+        Ok(self.owned.get(&self.untyped_guid(name)?).copied().unwrap_or(0.0))
+    }
+
+    pub fn set(&mut self, name: &str, val: f32) -> Result<(), SaveError> {
+        let guid = self.untyped_guid(name)?;
+        self.owned.insert(guid, val);
+        Ok(())
+    }
+
+    // Registered name, or the guid for resources the registry doesn't know yet
+    pub fn named(&self) -> Vec<(String, f32)> {
+        let mut named: Vec<(String, f32)> = self.owned.iter()
+            .map(|(guid, val)| (self.registry.name(guid).map_or_else(|| guid.to_hex(), |name| name.to_string()), *val))
+            .collect();
+        named.sort_by(|a, b| a.0.cmp(&b.0));
+        named
+    }
+}
+
+impl SaveFile {
+    fn typed_resource(&self, guid: &Guid) -> Option<f32> {
+        Some(match guid.0 {
+            BISMOR => self.minerals.bismor,
+            ENOR => self.minerals.enor,
+            JADIZ => self.minerals.jadiz,
+            CROPPA => self.minerals.croppa,
+            MAGNITE => self.minerals.magnite,
+            UMANITE => self.minerals.umanite,
+            YEAST => self.brewing.yeast,
+            STARCH => self.brewing.starch,
+            BARLEY => self.brewing.barley,
+            MALT => self.brewing.malt,
+            ERROR_CORES => self.error_cores,
+            BLANK_CORES => self.blank_cores,
+            _ => return None
+        })
+    }
+
+    fn typed_resource_mut(&mut self, guid: &Guid) -> Option<&mut f32> {
+        Some(match guid.0 {
+            BISMOR => &mut self.minerals.bismor,
+            ENOR => &mut self.minerals.enor,
+            JADIZ => &mut self.minerals.jadiz,
+            CROPPA => &mut self.minerals.croppa,
+            MAGNITE => &mut self.minerals.magnite,
+            UMANITE => &mut self.minerals.umanite,
+            YEAST => &mut self.brewing.yeast,
+            STARCH => &mut self.brewing.starch,
+            BARLEY => &mut self.brewing.barley,
+            MALT => &mut self.brewing.malt,
+            ERROR_CORES => &mut self.error_cores,
+            BLANK_CORES => &mut self.blank_cores,
+            _ => return None
+        })
+    }
+
+    // Any registered resource by name, the ones with a typed field are read from it
+    pub fn resource(&self, name: &str) -> Result<f32, SaveError> {
+        let guid = self.resources.registry.guid(name).ok_or_else(|| SaveError::UnknownResource(name.to_string()))?;
+        match self.typed_resource(&guid) {
+            Some(val) => Ok(val),
+            None => self.resources.get(name)
+        }
+    }
+
+    pub fn set_resource(&mut self, name: &str, val: f32) -> Result<(), SaveError> {
+        let guid = self.resources.registry.guid(name).ok_or_else(|| SaveError::UnknownResource(name.to_string()))?;
+        match self.typed_resource_mut(&guid) {
+            Some(field) => {
+                *field = val;
+                Ok(())
+            },
+            None => self.resources.set(name, val)
+        }
+    }
+}
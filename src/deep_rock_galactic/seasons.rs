@@ -0,0 +1,96 @@
+use crate::gvas::{self, Property, PropertyValue, StructValue};
+use super::{SaveError, SaveFile};
+
+pub const SEASON_XP_PER_LEVEL: u32 = 5000;
+pub const MAX_SEASON_LEVEL: u32 = 100;
+
+const SEASONS: &str = "Seasons";
+const SEASON_ID: &str = "SeasonID";
+const SEASON_XP: &str = "SeasonXP";
+const CLAIMED_NODES: &str = "ClaimedNodes";
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SeasonProgress {
+    pub season: u32,
+    pub xp: u32,
+    pub claimed_nodes: Vec<u32>
+}
+
+impl SeasonProgress {
+    pub fn level(&self) -> u32 {
+        (self.xp / SEASON_XP_PER_LEVEL + 1).min(MAX_SEASON_LEVEL)
+    }
+
+    pub fn set_level(&mut self, level: u32) -> Result<(), SaveError> {
+        if !(1..=MAX_SEASON_LEVEL).contains(&level) {
+            return Err(SaveError::InvalidLevel(level));
+        }
+        self.xp = (level - 1) * SEASON_XP_PER_LEVEL;
+        Ok(())
+    }
+
+    pub fn is_claimed(&self, node: u32) -> bool {
+        self.claimed_nodes.contains(&node)
+    }
+
+    pub fn claim(&mut self, node: u32) {
+        if !self.is_claimed(node) {
+            self.claimed_nodes.push(node);
+        }
+    }
+}
+
+fn get_season(element: &StructValue) -> Option<SeasonProgress> {
+    let element = element.properties()?;
+    let season = element.iter().find(|p| p.name == SEASON_ID)?.value.as_int()?;
+    let xp = element.iter().find(|p| p.name == SEASON_XP)?.value.as_int()?;
+    let claimed_nodes = match element.iter().find(|p| p.name == CLAIMED_NODES) {
+        Some(e) => e.value.as_int_array()?.into_iter().map(|node| node as u32).collect(),
+        None => Vec::new()
+    };
+    Some(SeasonProgress { season: season as u32, xp: xp as u32, claimed_nodes })
+}
+
+fn season_id(element: &[Property]) -> Option<i32> {
+    element.iter().find(|p| p.name == SEASON_ID)?.value.as_int()
+}
+
+pub(super) fn load(properties: &[Property]) -> Result<Vec<SeasonProgress>, SaveError> {
+    match gvas::find_property(properties, SEASONS) {
+        Some(e) => e.value.as_struct_array()
+            .and_then(|elements| elements.iter().map(get_season).collect())
+            .ok_or_else(|| SaveError::unexpected_type(SEASONS, "an array of seasons")),
+        None => Ok(Vec::new())
+    }
+}
+
+pub(super) fn save(seasons: &[SeasonProgress], properties: &mut Vec<Property>) -> Result<(), SaveError> {
+    gvas::upsert_struct_array(properties, SEASONS, "SeasonSave", seasons, season_id,
+        |season| vec![Property::new(SEASON_ID, PropertyValue::Int(season.season as i32))],
+        |element, season| {
+            gvas::set_property(element, SEASON_XP, PropertyValue::Int(season.xp as i32));
+            // the game leaves the list out until a node is claimed
+            if !season.claimed_nodes.is_empty() || element.iter().any(|p| p.name == CLAIMED_NODES) {
+                let claimed: Vec<i32> = season.claimed_nodes.iter().map(|node| *node as i32).collect();
+                gvas::set_property(element, CLAIMED_NODES, PropertyValue::int_array(&claimed));
+            }
+            Ok(())
+        })
+}
+
+impl SaveFile {
+    pub fn season(&self, season: u32) -> Option<&SeasonProgress> {
+        self.seasons.iter().find(|e| e.season == season)
+    }
+
+    pub fn season_mut(&mut self, season: u32) -> &mut SeasonProgress {
+        let pos = match self.seasons.iter().position(|e| e.season == season) {
+            Some(pos) => pos,
+            None => {
+                self.seasons.push(SeasonProgress { season, ..SeasonProgress::default() });
+                self.seasons.len() - 1
+            }
+        };
+        &mut self.seasons[pos]
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use crate::gvas::{self, Property};
+use super::{DwarfClass, SaveFile};
+
+const MISSIONS_COMPLETED: &str = "MissionsCompleted";
+const DEEP_DIVES_COMPLETED: &str = "DeepDivesCompleted";
+const ELITE_DEEP_DIVES_COMPLETED: &str = "EliteDeepDivesCompleted";
+const WEEKLY_DEEP_DIVE_COMPLETED: &str = "bWeeklyDeepDiveCompleted";
+const WEEKLY_ELITE_DEEP_DIVE_COMPLETED: &str = "bWeeklyEliteDeepDiveCompleted";
+
+// Counters the game hasn't written yet read as zero
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MissionStats {
+    pub missions_completed: HashMap<DwarfClass, u32>,
+    pub deep_dives_completed: u32,
+    pub elite_deep_dives_completed: u32,
+    pub weekly_deep_dive_completed: bool,
+    pub weekly_elite_deep_dive_completed: bool
+}
+
+impl MissionStats {
+    pub fn total_missions_completed(&self) -> u32 {
+        self.missions_completed.values().sum()
+    }
+}
+
+fn get_count(properties: &[Property], name: &str) -> u32 {
+    properties.iter().find(|p| p.name == name).and_then(|p| p.value.as_int()).unwrap_or(0) as u32
+}
+
+fn get_flag(properties: &[Property], name: &str) -> bool {
+    gvas::find_property(properties, name).and_then(|p| p.value.as_bool()).unwrap_or(false)
+}
+
+impl SaveFile {
+    pub fn mission_stats(&self) -> MissionStats {
+        let properties = &self.gvas.properties;
+        let missions_completed = DwarfClass::ALL.iter().map(|class| {
+            let class_properties = gvas::find_property_list(properties, &|list| SaveFile::is_class_properties(list, class.id()));
+            (*class, class_properties.map_or(0, |list| get_count(list, MISSIONS_COMPLETED)))
+        }).collect();
+
+        let count = |name| gvas::find_property(properties, name).and_then(|p| p.value.as_int()).unwrap_or(0) as u32;
+        MissionStats {
+            missions_completed,
+            deep_dives_completed: count(DEEP_DIVES_COMPLETED),
+            elite_deep_dives_completed: count(ELITE_DEEP_DIVES_COMPLETED),
+            weekly_deep_dive_completed: get_flag(properties, WEEKLY_DEEP_DIVE_COMPLETED),
+            weekly_elite_deep_dive_completed: get_flag(properties, WEEKLY_ELITE_DEEP_DIVE_COMPLETED)
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt;
+
+use super::{Cost, Minerals, SaveError, SaveFile};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mineral {
+    Bismor,
+    Croppa,
+    Enor,
+    Jadiz,
+    Magnite,
+    Umanite
+}
+
+impl Mineral {
+    pub const ALL: [Mineral; 6] = [Mineral::Bismor, Mineral::Croppa, Mineral::Enor, Mineral::Jadiz, Mineral::Magnite, Mineral::Umanite];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mineral::Bismor => "bismor",
+            Mineral::Croppa => "croppa",
+            Mineral::Enor => "enor",
+            Mineral::Jadiz => "jadiz",
+            Mineral::Magnite => "magnite",
+            Mineral::Umanite => "umanite"
+        }
+    }
+
+    fn cost(&self, amount: u32) -> Cost {
+        let mut cost = Cost::default();
+        *match self {
+            Mineral::Bismor => &mut cost.bismor,
+            Mineral::Croppa => &mut cost.croppa,
+            Mineral::Enor => &mut cost.enor,
+            Mineral::Jadiz => &mut cost.jadiz,
+            Mineral::Magnite => &mut cost.magnite,
+            Mineral::Umanite => &mut cost.umanite
+        } = amount;
+        cost
+    }
+}
+
+impl fmt::Display for Mineral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// The terminal's prices move around, these are only a starting point
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeRates {
+    // Units handed over for every unit received when swapping minerals
+    pub exchange: u32,
+    // Credits paid per unit bought
+    pub buy: u32,
+    // Credits received per unit sold
+    pub sell: u32
+}
+
+impl Default for TradeRates {
+    fn default() -> Self {
+        TradeRates { exchange: 2, buy: 60, sell: 15 }
+    }
+}
+
+// Amounts are what's received for exchanges and buys, what's handed over for sells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trade {
+    Exchange { from: Mineral, to: Mineral, amount: u32 },
+    Buy { mineral: Mineral, amount: u32 },
+    Sell { mineral: Mineral, amount: u32 }
+}
+
+impl fmt::Display for Trade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trade::Exchange { from, to, amount } => write!(f, "exchange {} for {} {}", from, amount, to),
+            Trade::Buy { mineral, amount } => write!(f, "buy {} {}", amount, mineral),
+            Trade::Sell { mineral, amount } => write!(f, "sell {} {}", amount, mineral)
+        }
+    }
+}
+
+impl Minerals {
+    pub fn get(&self, mineral: Mineral) -> f32 {
+        match mineral {
+            Mineral::Bismor => self.bismor,
+            Mineral::Croppa => self.croppa,
+            Mineral::Enor => self.enor,
+            Mineral::Jadiz => self.jadiz,
+            Mineral::Magnite => self.magnite,
+            Mineral::Umanite => self.umanite
+        }
+    }
+
+    pub fn get_mut(&mut self, mineral: Mineral) -> &mut f32 {
+        match mineral {
+            Mineral::Bismor => &mut self.bismor,
+            Mineral::Croppa => &mut self.croppa,
+            Mineral::Enor => &mut self.enor,
+            Mineral::Jadiz => &mut self.jadiz,
+            Mineral::Magnite => &mut self.magnite,
+            Mineral::Umanite => &mut self.umanite
+        }
+    }
+
+    // Credits aren't part of Minerals so buying and selling goes through the caller's balance
+    pub fn trade(&mut self, rates: &TradeRates, trade: &Trade, credits: &mut u32) -> Result<(), SaveError> {
+        // Amounts too large for a u32 can't be traded
+        let invalid = || SaveError::InvalidTrade(trade.to_string());
+        let price = match trade {
+            Trade::Exchange { from, to, .. } if from == to => return Err(invalid()),
+            Trade::Exchange { from, amount, .. } => from.cost(amount.checked_mul(rates.exchange).ok_or_else(invalid)?),
+            Trade::Buy { amount, .. } => Cost { credits: amount.checked_mul(rates.buy).ok_or_else(invalid)?, ..Cost::default() },
+            Trade::Sell { mineral, amount } => mineral.cost(*amount)
+        };
+        let received = match trade {
+            Trade::Sell { amount, .. } => amount.checked_mul(rates.sell).filter(|received| credits.checked_add(*received).is_some()).ok_or_else(invalid)?,
+            _ => 0
+        };
+        if let Some(missing) = price.shortfall(*credits, self) {
+            return Err(SaveError::InsufficientFunds { item: trade.to_string(), missing });
+        }
+
+        *credits = *credits - price.credits + received;
+        self.spend(&price);
+        match trade {
+            Trade::Exchange { to, amount, .. } => *self.get_mut(*to) += *amount as f32,
+            Trade::Buy { mineral, amount } => *self.get_mut(*mineral) += *amount as f32,
+            Trade::Sell { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+impl Cost {
+    fn mineral(&self, mineral: Mineral) -> u32 {
+        match mineral {
+            Mineral::Bismor => self.bismor,
+            Mineral::Croppa => self.croppa,
+            Mineral::Enor => self.enor,
+            Mineral::Jadiz => self.jadiz,
+            Mineral::Magnite => self.magnite,
+            Mineral::Umanite => self.umanite
+        }
+    }
+
+    // Trades that make this affordable, spare minerals are swapped first, then spare credits buy the rest
+    // and spare minerals get sold if credits run short. `None` when even that isn't enough
+    pub fn trades_needed(&self, credits: u32, minerals: &Minerals, rates: &TradeRates) -> Option<Vec<Trade>> {
+        let mut trades = Vec::new();
+        let mut spare: Vec<(Mineral, i64)> = Mineral::ALL.iter()
+            .map(|mineral| (*mineral, minerals.get(*mineral).max(0.0).floor() as i64 - self.mineral(*mineral) as i64))
+            .collect();
+        let mut spare_credits = credits as i64 - self.credits as i64;
+
+        for i in 0..spare.len() {
+            let (mineral, mut missing) = (spare[i].0, -spare[i].1);
+            while missing > 0 {
+                // Swap from whatever there's most of to spare
+                let Some(from) = (0..spare.len()).filter(|j| spare[*j].1 >= rates.exchange as i64 && rates.exchange > 0).max_by_key(|j| spare[*j].1) else {
+                    break;
+                };
+                let amount = missing.min(spare[from].1 / rates.exchange as i64);
+                spare[from].1 -= amount * rates.exchange as i64;
+                missing -= amount;
+                trades.push(Trade::Exchange { from: spare[from].0, to: mineral, amount: amount as u32 });
+            }
+            if missing > 0 {
+                spare_credits -= missing * rates.buy as i64;
+                trades.push(Trade::Buy { mineral, amount: missing as u32 });
+            }
+            spare[i].1 = spare[i].1.max(0);
+        }
+
+        while spare_credits < 0 {
+            let (mineral, amount) = spare.iter_mut().filter(|(_, amount)| *amount > 0).max_by_key(|(_, amount)| *amount)?;
+            if rates.sell == 0 {
+                return None;
+            }
+            let sold = ((-spare_credits) as u64).div_ceil(rates.sell as u64).min(*amount as u64) as i64;
+            *amount -= sold;
+            spare_credits += sold * rates.sell as i64;
+            trades.push(Trade::Sell { mineral: *mineral, amount: sold as u32 });
+        }
+
+        // Sells have to happen before the buys they pay for
+        trades.sort_by_key(|trade| match trade {
+            Trade::Exchange { .. } => 0,
+            Trade::Sell { .. } => 1,
+            Trade::Buy { .. } => 2
+        });
+        Some(trades)
+    }
+}
+
+impl SaveFile {
+    // Trades needed to forge an overclock or cosmetic, empty when it's already affordable
+    pub fn trades_to_forge(&self, uuid: &str, rates: &TradeRates) -> Result<Vec<Trade>, SaveError> {
+        let (_, cost) = self.matrix_core_cost(uuid)?;
+        cost.trades_needed(self.credits, &self.minerals, rates).ok_or_else(|| SaveError::InsufficientFunds {
+            item: uuid.to_string(),
+            missing: cost.shortfall(self.credits, &self.minerals).unwrap_or_default()
+        })
+    }
+
+    pub fn trade(&mut self, rates: &TradeRates, trade: &Trade) -> Result<(), SaveError> {
+        self.minerals.trade(rates, trade, &mut self.credits)
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+use crate::gvas::{Guid, Property};
+use super::{DwarfClass, SaveError, SaveFile};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum VanityKind {
+    ArmorPaintjob,
+    Headwear,
+    Moustache,
+    Beard,
+    Sideburns,
+    WeaponFramework,
+    VictoryPose
+}
+
+impl VanityKind {
+    pub const ALL: [VanityKind; 7] = [
+        VanityKind::ArmorPaintjob,
+        VanityKind::Headwear,
+        VanityKind::Moustache,
+        VanityKind::Beard,
+        VanityKind::Sideburns,
+        VanityKind::WeaponFramework,
+        VanityKind::VictoryPose
+    ];
+
+    fn property_name(self) -> &'static str {
+        match self {
+            VanityKind::ArmorPaintjob => "UnlockedArmorPaintjobs",
+            VanityKind::Headwear => "UnlockedHeadwear",
+            VanityKind::Moustache => "UnlockedMoustaches",
+            VanityKind::Beard => "UnlockedBeards",
+            VanityKind::Sideburns => "UnlockedSideburns",
+            VanityKind::WeaponFramework => "UnlockedWeaponFrameworks",
+            VanityKind::VictoryPose => "UnlockedVictoryPoses"
+        }
+    }
+}
+
+// Items shared by every dwarf have no class
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VanityItem {
+    pub kind: VanityKind,
+    pub name: String,
+    #[serde(default)]
+    pub class: Option<DwarfClass>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct VanityCatalog {
+    pub items: HashMap<String, VanityItem>
+}
+
+impl VanityCatalog {
+    pub fn from_json(json: &str) -> Result<Self, SaveError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn get(&self, guid: &Guid) -> Option<&VanityItem> {
+        self.items.get(&guid.to_hex())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Vanity {
+    pub owned: HashMap<VanityKind, Vec<Guid>>
+}
+
+impl Vanity {
+    pub(super) fn load(properties: &[Property]) -> Result<Vanity, SaveError> {
+        let owned = VanityKind::ALL.iter()
+            .map(|kind| Ok((*kind, SaveFile::get_optional_guid_array(properties, kind.property_name())?)))
+            .collect::<Result<HashMap<_, _>, SaveError>>()?;
+        Ok(Vanity { owned })
+    }
+
+    pub(super) fn save(&self, properties: &mut Vec<Property>) -> Result<(), SaveError> {
+        for kind in VanityKind::ALL {
+            SaveFile::set_optional_guid_array(properties, kind.property_name(), self.owned.get(&kind).map_or(&[], |guids| guids.as_slice()))?;
+        }
+        Ok(())
+    }
+
+    pub fn owns(&self, guid: &Guid) -> bool {
+        self.owned.values().any(|guids| guids.contains(guid))
+    }
+
+    // The catalog decides which list the item goes in
+    pub fn unlock(&mut self, catalog: &VanityCatalog, guid: Guid) -> Result<(), SaveError> {
+        let item = catalog.get(&guid).ok_or(SaveError::NotInCatalog(guid))?;
+        self.unlock_kind(item.kind, guid);
+        Ok(())
+    }
+
+    // For items the catalog doesn't know about
+    pub fn unlock_kind(&mut self, kind: VanityKind, guid: Guid) {
+        let owned = self.owned.entry(kind).or_default();
+        if !owned.contains(&guid) {
+            owned.push(guid);
+        }
+    }
+
+    pub fn lock(&mut self, guid: &Guid) {
+        for guids in self.owned.values_mut() {
+            guids.retain(|owned| owned != guid);
+        }
+    }
+
+    // Catalog items of a kind the account doesn't have yet
+    pub fn missing(&self, catalog: &VanityCatalog, kind: VanityKind) -> Vec<Guid> {
+        let mut missing: Vec<Guid> = catalog.items.iter()
+            .filter(|(_, item)| item.kind == kind)
+            .filter_map(|(uuid, _)| Guid::from_hex(uuid))
+            .filter(|guid| !self.owns(guid))
+            .collect();
+        missing.sort();
+        missing
+    }
+}
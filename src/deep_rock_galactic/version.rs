@@ -0,0 +1,59 @@
+use std::fmt;
+
+use crate::gvas::SaveHeader;
+use super::SaveError;
+
+pub const SAVE_GAME_CLASS: &str = "/Script/FSD.FSDSaveGame";
+pub const SAVE_GAME_VERSION: u32 = 2;
+pub const PACKAGE_VERSION: u32 = 522;
+pub const ENGINE_VERSION: (u16, u16) = (4, 27);
+pub const CUSTOM_VERSION_FORMAT: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionWarning {
+    PackageVersion(u32),
+    EngineVersion { major: u16, minor: u16, branch: String },
+    CustomVersionFormat(u32)
+}
+
+impl fmt::Display for VersionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionWarning::PackageVersion(version) => write!(f, "unknown package version {}, expected {}", version, PACKAGE_VERSION),
+            VersionWarning::EngineVersion { major, minor, branch } => write!(f, "unknown engine version {}.{} ({}), expected {}.{}", major, minor, branch, ENGINE_VERSION.0, ENGINE_VERSION.1),
+            VersionWarning::CustomVersionFormat(format) => write!(f, "unknown custom version format {}, expected {}", format, CUSTOM_VERSION_FORMAT)
+        }
+    }
+}
+
+// A different save class or save game version means this isn't a save we know how to edit,
+// anything else only gets reported since the property tree itself is version independent
+pub fn check_header(header: &SaveHeader) -> Result<Vec<VersionWarning>, SaveError> {
+    if header.save_game_class != SAVE_GAME_CLASS {
+        return Err(SaveError::UnsupportedSave(format!("save game class {}", header.save_game_class)));
+    }
+
+    if header.save_game_version != SAVE_GAME_VERSION {
+        return Err(SaveError::UnsupportedSave(format!("save game version {}", header.save_game_version)));
+    }
+
+    let mut warnings = Vec::new();
+    if header.package_version != PACKAGE_VERSION {
+        warnings.push(VersionWarning::PackageVersion(header.package_version));
+    }
+
+    let engine_version = &header.engine_version;
+    if (engine_version.major, engine_version.minor) != ENGINE_VERSION {
+        warnings.push(VersionWarning::EngineVersion {
+            major: engine_version.major,
+            minor: engine_version.minor,
+            branch: engine_version.branch.clone()
+        });
+    }
+
+    if header.custom_version_format != CUSTOM_VERSION_FORMAT {
+        warnings.push(VersionWarning::CustomVersionFormat(header.custom_version_format));
+    }
+
+    Ok(warnings)
+}
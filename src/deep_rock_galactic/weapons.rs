@@ -0,0 +1,266 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Serialize, Deserialize};
+use crate::gvas::{self, Guid, Property, PropertyValue, StructValue, Value};
+use super::{Cost, DwarfClass, OverclockState, SaveError, SaveFile};
+
+const OWNED_UPGRADES: &str = "OwnedUpgrades";
+const WEAPON_LOADOUTS: &str = "WeaponLoadouts";
+const WEAPON_ID: &str = "WeaponID";
+const LOADOUT_INDEX: &str = "LoadoutIndex";
+const SELECTED_UPGRADES: &str = "SelectedUpgrades";
+const EQUIPPED_OVERCLOCK: &str = "EquippedOverclock";
+const WEAPON_MAINTENANCE: &str = "WeaponMaintenance";
+const MAINTENANCE_LEVEL: &str = "Level";
+const MAINTENANCE_XP: &str = "XP";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Weapon {
+    pub class: DwarfClass,
+    pub name: String
+}
+
+// `weapon` is the weapon name, same as Overclock.weapon
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Upgrade {
+    pub weapon: String,
+    pub name: String,
+    pub tier: u32,
+    pub cost: Cost
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct WeaponCatalog {
+    pub weapons: HashMap<String, Weapon>,
+    pub upgrades: HashMap<String, Upgrade>
+}
+
+impl WeaponCatalog {
+    pub fn from_json(json: &str) -> Result<Self, SaveError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn weapon(&self, guid: &Guid) -> Option<&Weapon> {
+        self.weapons.get(&guid.to_hex())
+    }
+
+    pub fn upgrade(&self, guid: &Guid) -> Option<&Upgrade> {
+        self.upgrades.get(&guid.to_hex())
+    }
+
+    pub fn weapon_id(&self, name: &str) -> Option<Guid> {
+        self.weapons.iter().find(|(_, weapon)| weapon.name == name).and_then(|(uuid, _)| Guid::from_hex(uuid))
+    }
+
+    fn weapon_id_or_err(&self, name: &str) -> Result<Guid, SaveError> {
+        self.weapon_id(name).ok_or_else(|| SaveError::UnknownWeapon(name.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeaponLoadout {
+    pub weapon: Guid,
+    pub loadout: u32,
+    pub selected: Vec<Guid>,
+    pub overclock: Option<Guid>
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Maintenance {
+    pub level: u32,
+    pub xp: u32
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WeaponUpgrades {
+    pub owned: Vec<Guid>,
+    pub loadouts: Vec<WeaponLoadout>,
+    pub maintenance: HashMap<Guid, Maintenance>
+}
+
+fn get_loadout(element: &StructValue) -> Option<WeaponLoadout> {
+    let element = element.properties()?;
+    let weapon = element.iter().find(|p| p.name == WEAPON_ID)?.value.as_guid()?;
+    let loadout = element.iter().find(|p| p.name == LOADOUT_INDEX)?.value.as_int()?;
+    let selected = match element.iter().find(|p| p.name == SELECTED_UPGRADES) {
+        Some(e) => e.value.as_guid_array()?,
+        None => Vec::new()
+    };
+    let overclock = match element.iter().find(|p| p.name == EQUIPPED_OVERCLOCK) {
+        Some(e) => Some(e.value.as_guid()?),
+        None => None
+    };
+    Some(WeaponLoadout { weapon, loadout: loadout as u32, selected, overclock })
+}
+
+fn loadout_key(element: &[Property]) -> Option<(Guid, i32)> {
+    let weapon = element.iter().find(|p| p.name == WEAPON_ID)?.value.as_guid()?;
+    let loadout = element.iter().find(|p| p.name == LOADOUT_INDEX)?.value.as_int()?;
+    Some((weapon, loadout))
+}
+
+// Loadout entries keep whatever else the game stores in them, only the selection is replaced
+fn set_selected(element: &mut Vec<Property>, loadout: &WeaponLoadout) -> Result<(), SaveError> {
+    match element.iter_mut().find(|p| p.name == SELECTED_UPGRADES) {
+        Some(e) => *e.value.as_struct_array_mut().ok_or_else(|| SaveError::unexpected_type(SELECTED_UPGRADES, "an array of guids"))? =
+            loadout.selected.iter().map(|guid| StructValue::Guid(*guid)).collect(),
+        None if !loadout.selected.is_empty() => element.push(Property::new(SELECTED_UPGRADES, PropertyValue::guid_array(SELECTED_UPGRADES, &loadout.selected))),
+        None => {}
+    }
+
+    let pos = element.iter().position(|p| p.name == EQUIPPED_OVERCLOCK);
+    match (pos, loadout.overclock) {
+        (Some(pos), Some(overclock)) => element[pos].value = PropertyValue::guid(overclock),
+        (Some(pos), None) => {
+            element.remove(pos);
+        },
+        (None, Some(overclock)) => element.push(Property::new(EQUIPPED_OVERCLOCK, PropertyValue::guid(overclock))),
+        (None, None) => {}
+    }
+    Ok(())
+}
+
+fn get_maintenance(properties: &[Property]) -> Result<HashMap<Guid, Maintenance>, SaveError> {
+    let maintenance = match gvas::find_property(properties, WEAPON_MAINTENANCE) {
+        Some(e) => e,
+        None => return Ok(HashMap::new())
+    };
+
+    maintenance.value.map_entries()
+        .and_then(|entries| entries.iter().map(|(k, v)| {
+            let v = v.properties()?;
+            let level = v.iter().find(|p| p.name == MAINTENANCE_LEVEL)?.value.as_int()?;
+            let xp = v.iter().find(|p| p.name == MAINTENANCE_XP)?.value.as_int()?;
+            Some((k.as_guid()?, Maintenance { level: level as u32, xp: xp as u32 }))
+        }).collect())
+        .ok_or_else(|| SaveError::unexpected_type(WEAPON_MAINTENANCE, "a map of weapon maintenance"))
+}
+
+fn set_maintenance(properties: &mut Vec<Property>, maintenance: &HashMap<Guid, Maintenance>) -> Result<(), SaveError> {
+    let mut weapons: Vec<_> = maintenance.iter().collect();
+    weapons.sort_by_key(|(guid, _)| **guid);
+    gvas::upsert_map(properties, WEAPON_MAINTENANCE, ("StructProperty", "StructProperty"), &weapons, Value::as_guid,
+        |(guid, _)| (Value::Struct(StructValue::Guid(**guid)), Value::Struct(StructValue::Properties(Vec::new()))),
+        |value, (_, weapon)| {
+            let value = value.properties_mut().ok_or_else(|| SaveError::unexpected_type(WEAPON_MAINTENANCE, "a map of weapon maintenance"))?;
+            gvas::set_property(value, MAINTENANCE_LEVEL, PropertyValue::Int(weapon.level as i32));
+            gvas::set_property(value, MAINTENANCE_XP, PropertyValue::Int(weapon.xp as i32));
+            Ok(())
+        })
+}
+
+impl WeaponUpgrades {
+    pub(super) fn load(properties: &[Property]) -> Result<WeaponUpgrades, SaveError> {
+        let owned = SaveFile::get_optional_guid_array(properties, OWNED_UPGRADES)?;
+        let loadouts = match gvas::find_property(properties, WEAPON_LOADOUTS) {
+            Some(e) => e.value.as_struct_array()
+                .and_then(|elements| elements.iter().map(get_loadout).collect())
+                .ok_or_else(|| SaveError::unexpected_type(WEAPON_LOADOUTS, "an array of weapon loadouts"))?,
+            None => Vec::new()
+        };
+        let maintenance = get_maintenance(properties)?;
+        Ok(WeaponUpgrades { owned, loadouts, maintenance })
+    }
+
+    pub(super) fn save(&self, properties: &mut Vec<Property>) -> Result<(), SaveError> {
+        SaveFile::set_optional_guid_array(properties, OWNED_UPGRADES, &self.owned)?;
+        set_maintenance(properties, &self.maintenance)?;
+
+        gvas::upsert_struct_array(properties, WEAPON_LOADOUTS, "WeaponLoadout", &self.loadouts, loadout_key,
+            |loadout| vec![
+                Property::new(WEAPON_ID, PropertyValue::guid(loadout.weapon)),
+                Property::new(LOADOUT_INDEX, PropertyValue::Int(loadout.loadout as i32))
+            ],
+            set_selected)
+    }
+
+    pub fn loadout(&self, weapon: &Guid, loadout: u32) -> Option<&WeaponLoadout> {
+        self.loadouts.iter().find(|e| e.weapon == *weapon && e.loadout == loadout)
+    }
+
+    pub fn loadout_mut(&mut self, weapon: &Guid, loadout: u32) -> Option<&mut WeaponLoadout> {
+        self.loadouts.iter_mut().find(|e| e.weapon == *weapon && e.loadout == loadout)
+    }
+
+    // Owned upgrades of a weapon, grouped by tier
+    pub fn owned_by_tier(&self, catalog: &WeaponCatalog, weapon: &str) -> BTreeMap<u32, Vec<Guid>> {
+        let mut tiers: BTreeMap<u32, Vec<Guid>> = BTreeMap::new();
+        for guid in &self.owned {
+            if let Some(upgrade) = catalog.upgrade(guid).filter(|upgrade| upgrade.weapon == weapon) {
+                tiers.entry(upgrade.tier).or_default().push(*guid);
+            }
+        }
+        tiers
+    }
+
+    // Selected upgrades that aren't owned, belong to another weapon or share a tier with an earlier pick
+    pub fn invalid_selections(&self, catalog: &WeaponCatalog) -> Vec<(Guid, u32, Guid)> {
+        let mut invalid = Vec::new();
+        for loadout in &self.loadouts {
+            let weapon = catalog.weapon(&loadout.weapon).map(|weapon| weapon.name.as_str());
+            let mut tiers = Vec::new();
+            for guid in &loadout.selected {
+                let valid = match catalog.upgrade(guid) {
+                    Some(upgrade) if self.owned.contains(guid) && Some(upgrade.weapon.as_str()) == weapon && !tiers.contains(&upgrade.tier) => {
+                        tiers.push(upgrade.tier);
+                        true
+                    },
+                    _ => false
+                };
+                if !valid {
+                    invalid.push((loadout.weapon, loadout.loadout, *guid));
+                }
+            }
+        }
+        invalid
+    }
+}
+
+impl SaveFile {
+    fn check_overclock(&self, catalog: &WeaponCatalog, weapon: &Guid, overclock: &Guid) -> Result<(), SaveError> {
+        let weapon_name = catalog.weapon(weapon).ok_or(SaveError::NotInCatalog(*weapon))?.name.as_str();
+        let cannot_equip = |reason: &'static str| SaveError::CannotEquip { overclock: overclock.to_hex(), weapon: weapon_name.to_string(), reason };
+
+        let oc = self.matrix_cores.overclocks.get(&overclock.to_hex()).ok_or(SaveError::NotInCatalog(*overclock))?;
+        if oc.weapon != weapon_name {
+            return Err(cannot_equip("it belongs to another weapon"));
+        }
+        if oc.state != OverclockState::Forged {
+            return Err(cannot_equip("it isn't forged"));
+        }
+        Ok(())
+    }
+
+    // `None` takes the overclock off again
+    pub fn equip_overclock(&mut self, catalog: &WeaponCatalog, weapon: &Guid, loadout: u32, overclock: Option<Guid>) -> Result<(), SaveError> {
+        if let Some(overclock) = &overclock {
+            self.check_overclock(catalog, weapon, overclock)?;
+        }
+
+        match self.weapons.loadout_mut(weapon, loadout) {
+            Some(e) => e.overclock = overclock,
+            None => self.weapons.loadouts.push(WeaponLoadout { weapon: *weapon, loadout, selected: Vec::new(), overclock })
+        }
+        Ok(())
+    }
+
+    // Weapons are looked up by the names the overclock catalog uses
+    pub fn maintenance(&self, catalog: &WeaponCatalog, weapon: &str) -> Result<Maintenance, SaveError> {
+        let weapon = catalog.weapon_id_or_err(weapon)?;
+        Ok(self.weapons.maintenance.get(&weapon).cloned().unwrap_or_default())
+    }
+
+    pub fn set_maintenance(&mut self, catalog: &WeaponCatalog, weapon: &str, level: u32, xp: u32) -> Result<(), SaveError> {
+        let weapon = catalog.weapon_id_or_err(weapon)?;
+        self.weapons.maintenance.insert(weapon, Maintenance { level, xp });
+        Ok(())
+    }
+
+    // Equipped overclocks that aren't forged (anymore) or sit on the wrong weapon
+    pub fn invalid_equipped_overclocks(&self, catalog: &WeaponCatalog) -> Vec<(Guid, u32, Guid)> {
+        self.weapons.loadouts.iter()
+            .filter_map(|e| Some((e.weapon, e.loadout, e.overclock?)))
+            .filter(|(weapon, _, overclock)| self.check_overclock(catalog, weapon, overclock).is_err())
+            .collect()
+    }
+}
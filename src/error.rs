@@ -0,0 +1,84 @@
+use std::{error::Error, fmt, io};
+
+use crate::{deep_rock_galactic::Cost, gvas::Guid};
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    UnexpectedEof { offset: usize, expected: &'static str },
+    InvalidData { offset: usize, expected: &'static str },
+    MissingSection { section: String },
+    UnexpectedType { section: String, expected: &'static str },
+    InvalidGuid(String),
+    UnsupportedSave(String),
+    UnknownResource(String),
+    TypedResource(String),
+    UnknownWeapon(String),
+    InvalidLevel(u32),
+    Overflow(&'static str),
+    CannotPromote { class: String, level: u32 },
+    NotInCatalog(Guid),
+    CannotEquip { overclock: String, weapon: String, reason: &'static str },
+    NotForgeable(String),
+    InsufficientFunds { item: String, missing: Cost },
+    InvalidTrade(String),
+    Catalog(serde_json::Error)
+}
+
+impl SaveError {
+    pub(crate) fn missing(section: &str) -> SaveError {
+        SaveError::MissingSection { section: section.to_string() }
+    }
+
+    pub(crate) fn unexpected_type(section: &str, expected: &'static str) -> SaveError {
+        SaveError::UnexpectedType { section: section.to_string(), expected }
+    }
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "io error: {}", e),
+            SaveError::UnexpectedEof { offset, expected } => write!(f, "unexpected end of file at offset {:#x}, expected {}", offset, expected),
+            SaveError::InvalidData { offset, expected } => write!(f, "invalid data at offset {:#x}, expected {}", offset, expected),
+            SaveError::MissingSection { section } => write!(f, "{} is missing from the save file", section),
+            SaveError::UnexpectedType { section, expected } => write!(f, "{} is not {}", section, expected),
+            SaveError::InvalidGuid(guid) => write!(f, "invalid guid {}", guid),
+            SaveError::UnsupportedSave(reason) => write!(f, "unsupported save file: {}", reason),
+            SaveError::UnknownResource(name) => write!(f, "unknown resource {}", name),
+            SaveError::TypedResource(name) => write!(f, "{} has its own SaveFile field, edit it there or through SaveFile::set_resource", name),
+            SaveError::UnknownWeapon(name) => write!(f, "unknown weapon {}", name),
+            SaveError::InvalidLevel(level) => write!(f, "level {} is out of range", level),
+            SaveError::Overflow(value) => write!(f, "{} would overflow", value),
+            SaveError::CannotPromote { class, level } => write!(f, "{} is level {}, promoting needs level {}", class, level, crate::deep_rock_galactic::MAX_LEVEL),
+            SaveError::NotInCatalog(guid) => write!(f, "{} is not in the catalog", guid),
+            SaveError::CannotEquip { overclock, weapon, reason } => write!(f, "can't equip {} on {}, {}", overclock, weapon, reason),
+            SaveError::NotForgeable(item) => write!(f, "{} isn't waiting to be forged", item),
+            SaveError::InsufficientFunds { item, missing } => write!(f, "can't afford {}, missing {}", item, missing),
+            SaveError::InvalidTrade(trade) => write!(f, "can't {}", trade),
+            SaveError::Catalog(e) => write!(f, "failed to parse catalog: {}", e)
+        }
+    }
+}
+
+impl Error for SaveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SaveError::Io(e) => Some(e),
+            SaveError::Catalog(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> SaveError {
+        SaveError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> SaveError {
+        SaveError::Catalog(e)
+    }
+}
@@ -0,0 +1,1182 @@
+use std::{collections::VecDeque, convert::TryInto, fmt};
+
+use crate::error::SaveError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+    pub fn from_hex(s: &str) -> Option<Guid> {
+        let bytes = hex::decode(s).ok()?;
+        Some(Guid(bytes.try_into().ok()?))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode_upper(self.0)
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+    pub changelist: u32,
+    pub branch: String
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomVersion {
+    pub key: Guid,
+    pub version: u32
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveHeader {
+    pub save_game_version: u32,
+    pub package_version: u32,
+    pub package_version_ue5: Option<u32>,
+    pub engine_version: EngineVersion,
+    pub custom_version_format: u32,
+    pub custom_versions: Vec<CustomVersion>,
+    pub save_game_class: String
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ByteValue {
+    Byte(u8),
+    Name(String)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StructValue {
+    Guid(Guid),
+    Properties(Vec<Property>),
+    Raw(Vec<u8>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Byte(u8),
+    Int8(i8),
+    Int16(i16),
+    Int(i32),
+    Int64(i64),
+    UInt16(u16),
+    UInt32(u32),
+    UInt64(u64),
+    Float(f32),
+    Double(f64),
+    Str(String),
+    Struct(StructValue)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrayValue {
+    Structs {
+        name: String,
+        struct_type: String,
+        struct_guid: Guid,
+        elements: Vec<StructValue>
+    },
+    Values(Vec<Value>),
+    Raw(Vec<u8>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetValue {
+    Elements {
+        removed: Vec<Value>,
+        elements: Vec<Value>
+    },
+    Raw(Vec<u8>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapValue {
+    Entries {
+        removed: Vec<Value>,
+        entries: Vec<(Value, Value)>
+    },
+    Raw(Vec<u8>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    Bool(bool),
+    Int8(i8),
+    Int16(i16),
+    Int(i32),
+    Int64(i64),
+    UInt16(u16),
+    UInt32(u32),
+    UInt64(u64),
+    Float(f32),
+    Double(f64),
+    Str(String),
+    Name(String),
+    Object(String),
+    Byte { enum_type: String, value: ByteValue },
+    Enum { enum_type: String, value: String },
+    Struct { struct_type: String, struct_guid: Guid, value: StructValue },
+    Array { inner_type: String, value: ArrayValue },
+    Set { inner_type: String, value: SetValue },
+    Map { key_type: String, value_type: String, value: MapValue },
+    Other { type_name: String, data: Vec<u8> }
+}
+
+// Strings can be stored in more than one way (an empty string with or without its
+// terminator, ASCII as UTF-16), so when writing a value back wouldn't give the bytes it
+// was read from those are kept and reused for as long as the value is left unchanged
+#[derive(Debug, Clone, PartialEq)]
+struct Original<T> {
+    value: T,
+    bytes: Vec<u8>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub name: String,
+    pub index: u32,
+    pub guid: Option<Guid>,
+    pub value: PropertyValue,
+    original: Option<Box<Original<Property>>>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gvas {
+    pub header: SaveHeader,
+    pub properties: Vec<Property>,
+    pub trailer: Vec<u8>,
+    original_header: Option<Original<SaveHeader>>
+}
+
+const GVAS_MAGIC: &[u8; 4] = b"GVAS";
+const NONE: &str = "None";
+// Deeper nesting than this only shows up in corrupted files, and would overflow the stack eventually
+const MAX_DEPTH: usize = 64;
+
+#[derive(Clone)]
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    base: usize,
+    depth: usize
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8], base: usize) -> Reader<'a> {
+        Reader { buf, pos: 0, base, depth: 0 }
+    }
+
+    fn offset(&self) -> usize {
+        self.base + self.pos
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+
+    fn eof(&self, expected: &'static str) -> SaveError {
+        SaveError::UnexpectedEof { offset: self.offset(), expected }
+    }
+
+    fn invalid(&self, offset: usize, expected: &'static str) -> SaveError {
+        SaveError::InvalidData { offset, expected }
+    }
+
+    fn read_bytes(&mut self, len: usize, expected: &'static str) -> Result<&'a [u8], SaveError> {
+        let end = self.pos.checked_add(len).ok_or_else(|| self.eof(expected))?;
+        let bytes = self.buf.get(self.pos..end).ok_or_else(|| self.eof(expected))?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self, expected: &'static str) -> Result<[u8; N], SaveError> {
+        let offset = self.offset();
+        self.read_bytes(N, expected)?.try_into().map_err(|_| self.invalid(offset, expected))
+    }
+
+    fn sub(&mut self, len: usize, expected: &'static str) -> Result<Reader<'a>, SaveError> {
+        let base = self.offset();
+        let depth = self.depth;
+        Ok(Reader { depth, ..Reader::new(self.read_bytes(len, expected)?, base) })
+    }
+
+    fn rest(&mut self) -> &'a [u8] {
+        let rest = &self.buf[self.pos.min(self.buf.len())..];
+        self.pos = self.buf.len();
+        rest
+    }
+
+    fn read_u8(&mut self) -> Result<u8, SaveError> {
+        Ok(self.read_array::<1>("u8")?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, SaveError> {
+        Ok(u16::from_le_bytes(self.read_array("u16")?))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, SaveError> {
+        Ok(u32::from_le_bytes(self.read_array("u32")?))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, SaveError> {
+        Ok(i32::from_le_bytes(self.read_array("i32")?))
+    }
+
+    fn read_guid(&mut self) -> Result<Guid, SaveError> {
+        Ok(Guid(self.read_array("guid")?))
+    }
+
+    fn read_string(&mut self) -> Result<String, SaveError> {
+        let offset = self.offset();
+        let len = self.read_i32()?;
+        if len == 0 {
+            return Ok(String::new());
+        }
+
+        if len < 0 {
+            let chars = len.checked_neg().ok_or_else(|| self.invalid(offset, "string length"))? as usize;
+            let bytes = self.read_bytes(chars.checked_mul(2).ok_or_else(|| self.invalid(offset, "string length"))?, "UTF-16 string")?;
+            let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+            match units.split_last() {
+                Some((0, units)) => String::from_utf16(units).map_err(|_| self.invalid(offset, "UTF-16 string")),
+                _ => Err(self.invalid(offset, "null terminated string"))
+            }
+        } else {
+            let bytes = self.read_bytes(len as usize, "string")?;
+            match bytes.split_last() {
+                Some((0, bytes)) => Ok(bytes.iter().map(|&b| b as char).collect()),
+                _ => Err(self.invalid(offset, "null terminated string"))
+            }
+        }
+    }
+
+    fn read_optional_guid(&mut self) -> Result<Option<Guid>, SaveError> {
+        match self.read_u8()? {
+            0 => Ok(None),
+            _ => Ok(Some(self.read_guid()?))
+        }
+    }
+}
+
+fn fixed_struct_size(struct_type: &str) -> Option<usize> {
+    match struct_type {
+        "Guid" | "Quat" | "LinearColor" => Some(16),
+        "DateTime" | "Timespan" | "IntPoint" | "Vector2D" => Some(8),
+        "Vector" | "Rotator" | "IntVector" => Some(12),
+        "Color" => Some(4),
+        _ => None
+    }
+}
+
+fn read_struct_value(r: &mut Reader, struct_type: &str) -> Result<StructValue, SaveError> {
+    if struct_type == "Guid" {
+        return Ok(StructValue::Guid(r.read_guid()?));
+    }
+
+    match fixed_struct_size(struct_type) {
+        Some(size) => Ok(StructValue::Raw(r.read_bytes(size, "struct")?.to_vec())),
+        None => Ok(StructValue::Properties(read_properties(r)?))
+    }
+}
+
+fn read_value(r: &mut Reader, type_name: &str, is_key: bool) -> Result<Value, SaveError> {
+    Ok(match type_name {
+        "BoolProperty" => Value::Bool(r.read_u8()? != 0),
+        "ByteProperty" => Value::Byte(r.read_u8()?),
+        "Int8Property" => Value::Int8(r.read_u8()? as i8),
+        "Int16Property" => Value::Int16(r.read_u16()? as i16),
+        "IntProperty" => Value::Int(r.read_i32()?),
+        "Int64Property" => Value::Int64(i64::from_le_bytes(r.read_array("i64")?)),
+        "UInt16Property" => Value::UInt16(r.read_u16()?),
+        "UInt32Property" => Value::UInt32(r.read_u32()?),
+        "UInt64Property" => Value::UInt64(u64::from_le_bytes(r.read_array("u64")?)),
+        "FloatProperty" => Value::Float(f32::from_le_bytes(r.read_array("f32")?)),
+        "DoubleProperty" => Value::Double(f64::from_le_bytes(r.read_array("f64")?)),
+        "StrProperty" | "NameProperty" | "ObjectProperty" | "EnumProperty" => Value::Str(r.read_string()?),
+        // map and set entries don't carry their struct type, keys are guids in every save seen so far
+        "StructProperty" if is_key => Value::Struct(StructValue::Guid(r.read_guid()?)),
+        "StructProperty" => Value::Struct(StructValue::Properties(read_properties(r)?)),
+        _ => return Err(r.invalid(r.offset(), "container element of a known type"))
+    })
+}
+
+fn read_values(r: &mut Reader, type_name: &str, is_key: bool) -> Result<Vec<Value>, SaveError> {
+    let count = r.read_u32()?;
+    let mut values = Vec::new();
+    for _ in 0..count {
+        values.push(read_value(r, type_name, is_key)?);
+    }
+    Ok(values)
+}
+
+fn read_array(r: &mut Reader, inner_type: &str) -> Result<ArrayValue, SaveError> {
+    let count = r.read_u32()?;
+    if inner_type != "StructProperty" {
+        let mut values = Vec::new();
+        for _ in 0..count {
+            values.push(read_value(r, inner_type, false)?);
+        }
+        return Ok(ArrayValue::Values(values));
+    }
+
+    let name = r.read_string()?;
+    let offset = r.offset();
+    if r.read_string()? != "StructProperty" {
+        return Err(r.invalid(offset, "StructProperty array header"));
+    }
+    let size = r.read_u32()? as usize;
+    let _index = r.read_u32()?;
+    let struct_type = r.read_string()?;
+    let struct_guid = r.read_guid()?;
+    let offset = r.offset();
+    if r.read_u8()? != 0 {
+        return Err(r.invalid(offset, "StructProperty array header"));
+    }
+
+    let mut data = r.sub(size, "struct array")?;
+    let mut elements = Vec::new();
+    for _ in 0..count {
+        elements.push(read_struct_value(&mut data, &struct_type)?);
+    }
+    if !data.is_empty() {
+        return Err(data.invalid(data.offset(), "end of struct array"));
+    }
+
+    Ok(ArrayValue::Structs { name, struct_type, struct_guid, elements })
+}
+
+fn read_set(r: &mut Reader, inner_type: &str) -> Result<SetValue, SaveError> {
+    let removed = read_values(r, inner_type, true)?;
+    let elements = read_values(r, inner_type, true)?;
+    Ok(SetValue::Elements { removed, elements })
+}
+
+fn read_map(r: &mut Reader, key_type: &str, value_type: &str) -> Result<MapValue, SaveError> {
+    let removed = read_values(r, key_type, true)?;
+    let count = r.read_u32()?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let key = read_value(r, key_type, true)?;
+        let value = read_value(r, value_type, false)?;
+        entries.push((key, value));
+    }
+    Ok(MapValue::Entries { removed, entries })
+}
+
+// Parses a size-delimited value, failing when the parser doesn't consume it exactly
+fn read_exact<T>(data: &Reader, parse: impl FnOnce(&mut Reader) -> Result<T, SaveError>) -> Result<T, SaveError> {
+    let mut r = data.clone();
+    let value = parse(&mut r)?;
+    if r.is_empty() {
+        Ok(value)
+    } else {
+        Err(r.invalid(r.offset(), "end of property value"))
+    }
+}
+
+fn read_property_value(type_name: &str, data: &Reader) -> Result<PropertyValue, SaveError> {
+    read_exact(data, |r| Ok(match type_name {
+        "Int8Property" => PropertyValue::Int8(r.read_u8()? as i8),
+        "Int16Property" => PropertyValue::Int16(r.read_u16()? as i16),
+        "IntProperty" => PropertyValue::Int(r.read_i32()?),
+        "Int64Property" => PropertyValue::Int64(i64::from_le_bytes(r.read_array("i64")?)),
+        "UInt16Property" => PropertyValue::UInt16(r.read_u16()?),
+        "UInt32Property" => PropertyValue::UInt32(r.read_u32()?),
+        "UInt64Property" => PropertyValue::UInt64(u64::from_le_bytes(r.read_array("u64")?)),
+        "FloatProperty" => PropertyValue::Float(f32::from_le_bytes(r.read_array("f32")?)),
+        "DoubleProperty" => PropertyValue::Double(f64::from_le_bytes(r.read_array("f64")?)),
+        "StrProperty" => PropertyValue::Str(r.read_string()?),
+        "NameProperty" => PropertyValue::Name(r.read_string()?),
+        "ObjectProperty" => PropertyValue::Object(r.read_string()?),
+        _ => return Err(r.invalid(r.offset(), "property of a known type"))
+    }))
+}
+
+fn read_property(r: &mut Reader) -> Result<Option<Property>, SaveError> {
+    let start = r.pos;
+    let property = match read_property_contents(r)? {
+        Some(property) => property,
+        None => return Ok(None)
+    };
+    Ok(Some(property.keeping_original(&r.buf[start..r.pos])))
+}
+
+fn read_property_contents(r: &mut Reader) -> Result<Option<Property>, SaveError> {
+    let name = r.read_string()?;
+    if name == NONE {
+        return Ok(None);
+    }
+
+    let type_name = r.read_string()?;
+    let size = r.read_u32()? as usize;
+    let index = r.read_u32()?;
+
+    let (guid, value) = match type_name.as_str() {
+        "BoolProperty" => {
+            let value = r.read_u8()? != 0;
+            let guid = r.read_optional_guid()?;
+            if size != 0 {
+                return Err(r.invalid(r.offset(), "empty BoolProperty value"));
+            }
+            (guid, PropertyValue::Bool(value))
+        },
+        "ByteProperty" => {
+            let enum_type = r.read_string()?;
+            let guid = r.read_optional_guid()?;
+            let data = r.sub(size, "ByteProperty value")?;
+            let value = if enum_type == NONE {
+                read_exact(&data, |r| Ok(ByteValue::Byte(r.read_u8()?)))?
+            } else {
+                read_exact(&data, |r| Ok(ByteValue::Name(r.read_string()?)))?
+            };
+            (guid, PropertyValue::Byte { enum_type, value })
+        },
+        "EnumProperty" => {
+            let enum_type = r.read_string()?;
+            let guid = r.read_optional_guid()?;
+            let value = read_exact(&r.sub(size, "EnumProperty value")?, |r| r.read_string())?;
+            (guid, PropertyValue::Enum { enum_type, value })
+        },
+        "StructProperty" => {
+            let struct_type = r.read_string()?;
+            let struct_guid = r.read_guid()?;
+            let guid = r.read_optional_guid()?;
+            let data = r.sub(size, "StructProperty value")?;
+            let value = read_exact(&data, |r| read_struct_value(r, &struct_type))
+                .unwrap_or_else(|_| StructValue::Raw(data.buf.to_vec()));
+            (guid, PropertyValue::Struct { struct_type, struct_guid, value })
+        },
+        "ArrayProperty" => {
+            let inner_type = r.read_string()?;
+            let guid = r.read_optional_guid()?;
+            let data = r.sub(size, "ArrayProperty value")?;
+            let value = read_exact(&data, |r| read_array(r, &inner_type))
+                .unwrap_or_else(|_| ArrayValue::Raw(data.buf.to_vec()));
+            (guid, PropertyValue::Array { inner_type, value })
+        },
+        "SetProperty" => {
+            let inner_type = r.read_string()?;
+            let guid = r.read_optional_guid()?;
+            let data = r.sub(size, "SetProperty value")?;
+            let value = read_exact(&data, |r| read_set(r, &inner_type))
+                .unwrap_or_else(|_| SetValue::Raw(data.buf.to_vec()));
+            (guid, PropertyValue::Set { inner_type, value })
+        },
+        "MapProperty" => {
+            let key_type = r.read_string()?;
+            let value_type = r.read_string()?;
+            let guid = r.read_optional_guid()?;
+            let data = r.sub(size, "MapProperty value")?;
+            let value = read_exact(&data, |r| read_map(r, &key_type, &value_type))
+                .unwrap_or_else(|_| MapValue::Raw(data.buf.to_vec()));
+            (guid, PropertyValue::Map { key_type, value_type, value })
+        },
+        _ => {
+            let guid = r.read_optional_guid()?;
+            let data = r.sub(size, "property value")?;
+            let value = read_property_value(&type_name, &data)
+                .unwrap_or_else(|_| PropertyValue::Other { type_name: type_name.clone(), data: data.buf.to_vec() });
+            (guid, value)
+        }
+    };
+
+    Ok(Some(Property { name, index, guid, value, original: None }))
+}
+
+fn read_properties(r: &mut Reader) -> Result<Vec<Property>, SaveError> {
+    if r.depth >= MAX_DEPTH {
+        return Err(r.invalid(r.offset(), "less deeply nested properties"));
+    }
+
+    r.depth += 1;
+    let mut properties = Vec::new();
+    while let Some(property) = read_property(r)? {
+        properties.push(property);
+    }
+    r.depth -= 1;
+    Ok(properties)
+}
+
+fn read_header(r: &mut Reader) -> Result<SaveHeader, SaveError> {
+    if &r.read_array::<4>("GVAS magic")? != GVAS_MAGIC {
+        return Err(r.invalid(0, "GVAS magic"));
+    }
+
+    let save_game_version = r.read_u32()?;
+    let package_version = r.read_u32()?;
+    let package_version_ue5 = if save_game_version >= 3 { Some(r.read_u32()?) } else { None };
+
+    let engine_version = EngineVersion {
+        major: r.read_u16()?,
+        minor: r.read_u16()?,
+        patch: r.read_u16()?,
+        changelist: r.read_u32()?,
+        branch: r.read_string()?
+    };
+
+    let custom_version_format = r.read_u32()?;
+    let custom_version_count = r.read_u32()?;
+    let mut custom_versions = Vec::new();
+    for _ in 0..custom_version_count {
+        custom_versions.push(CustomVersion {
+            key: r.read_guid()?,
+            version: r.read_u32()?
+        });
+    }
+
+    let save_game_class = r.read_string()?;
+
+    Ok(SaveHeader {
+        save_game_version,
+        package_version,
+        package_version_ue5,
+        engine_version,
+        custom_version_format,
+        custom_versions,
+        save_game_class
+    })
+}
+
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+    if s.is_empty() {
+        buf.extend(0i32.to_le_bytes());
+    } else if s.is_ascii() {
+        buf.extend((s.len() as i32 + 1).to_le_bytes());
+        buf.extend(s.as_bytes());
+        buf.push(0);
+    } else {
+        let units: Vec<u16> = s.encode_utf16().collect();
+        buf.extend((-(units.len() as i32 + 1)).to_le_bytes());
+        for unit in units {
+            buf.extend(unit.to_le_bytes());
+        }
+        buf.extend(0u16.to_le_bytes());
+    }
+}
+
+fn write_optional_guid(buf: &mut Vec<u8>, guid: &Option<Guid>) {
+    match guid {
+        Some(guid) => {
+            buf.push(1);
+            buf.extend(guid.0);
+        },
+        None => buf.push(0)
+    }
+}
+
+// Writes a size placeholder and returns its position for `patch_size`
+fn write_size(buf: &mut Vec<u8>) -> usize {
+    let pos = buf.len();
+    buf.extend(0u32.to_le_bytes());
+    pos
+}
+
+fn patch_size(buf: &mut [u8], size_pos: usize, start: usize) {
+    let size = (buf.len() - start) as u32;
+    buf[size_pos..size_pos + 4].copy_from_slice(&size.to_le_bytes());
+}
+
+fn write_struct_value(buf: &mut Vec<u8>, value: &StructValue) {
+    match value {
+        StructValue::Guid(guid) => buf.extend(guid.0),
+        StructValue::Properties(properties) => write_properties(buf, properties),
+        StructValue::Raw(data) => buf.extend(data)
+    }
+}
+
+fn write_value(buf: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Bool(value) => buf.push(*value as u8),
+        Value::Byte(value) => buf.push(*value),
+        Value::Int8(value) => buf.extend(value.to_le_bytes()),
+        Value::Int16(value) => buf.extend(value.to_le_bytes()),
+        Value::Int(value) => buf.extend(value.to_le_bytes()),
+        Value::Int64(value) => buf.extend(value.to_le_bytes()),
+        Value::UInt16(value) => buf.extend(value.to_le_bytes()),
+        Value::UInt32(value) => buf.extend(value.to_le_bytes()),
+        Value::UInt64(value) => buf.extend(value.to_le_bytes()),
+        Value::Float(value) => buf.extend(value.to_le_bytes()),
+        Value::Double(value) => buf.extend(value.to_le_bytes()),
+        Value::Str(value) => write_string(buf, value),
+        Value::Struct(value) => write_struct_value(buf, value)
+    }
+}
+
+fn write_values(buf: &mut Vec<u8>, values: &[Value]) {
+    buf.extend((values.len() as u32).to_le_bytes());
+    for value in values {
+        write_value(buf, value);
+    }
+}
+
+fn write_array(buf: &mut Vec<u8>, value: &ArrayValue) {
+    match value {
+        ArrayValue::Structs { name, struct_type, struct_guid, elements } => {
+            buf.extend((elements.len() as u32).to_le_bytes());
+            write_string(buf, name);
+            write_string(buf, "StructProperty");
+            let size_pos = write_size(buf);
+            buf.extend(0u32.to_le_bytes());
+            write_string(buf, struct_type);
+            buf.extend(struct_guid.0);
+            buf.push(0);
+
+            let start = buf.len();
+            for element in elements {
+                write_struct_value(buf, element);
+            }
+            patch_size(buf, size_pos, start);
+        },
+        ArrayValue::Values(values) => write_values(buf, values),
+        ArrayValue::Raw(data) => buf.extend(data)
+    }
+}
+
+fn write_set(buf: &mut Vec<u8>, value: &SetValue) {
+    match value {
+        SetValue::Elements { removed, elements } => {
+            write_values(buf, removed);
+            write_values(buf, elements);
+        },
+        SetValue::Raw(data) => buf.extend(data)
+    }
+}
+
+fn write_map(buf: &mut Vec<u8>, value: &MapValue) {
+    match value {
+        MapValue::Entries { removed, entries } => {
+            write_values(buf, removed);
+            buf.extend((entries.len() as u32).to_le_bytes());
+            for (key, value) in entries {
+                write_value(buf, key);
+                write_value(buf, value);
+            }
+        },
+        MapValue::Raw(data) => buf.extend(data)
+    }
+}
+
+fn write_property(buf: &mut Vec<u8>, property: &Property) {
+    if let Some(original) = property.original.as_ref().filter(|original| original.value.same_contents(property)) {
+        buf.extend(&original.bytes);
+        return;
+    }
+
+    write_string(buf, &property.name);
+    write_string(buf, property.value.type_name());
+    let size_pos = write_size(buf);
+    buf.extend(property.index.to_le_bytes());
+
+    match &property.value {
+        PropertyValue::Bool(value) => {
+            buf.push(*value as u8);
+            write_optional_guid(buf, &property.guid);
+            return;
+        },
+        PropertyValue::Byte { enum_type, .. } | PropertyValue::Enum { enum_type, .. } => write_string(buf, enum_type),
+        PropertyValue::Struct { struct_type, struct_guid, .. } => {
+            write_string(buf, struct_type);
+            buf.extend(struct_guid.0);
+        },
+        PropertyValue::Array { inner_type, .. } | PropertyValue::Set { inner_type, .. } => write_string(buf, inner_type),
+        PropertyValue::Map { key_type, value_type, .. } => {
+            write_string(buf, key_type);
+            write_string(buf, value_type);
+        },
+        _ => {}
+    }
+    write_optional_guid(buf, &property.guid);
+
+    let start = buf.len();
+    match &property.value {
+        PropertyValue::Bool(_) => {},
+        PropertyValue::Int8(value) => buf.extend(value.to_le_bytes()),
+        PropertyValue::Int16(value) => buf.extend(value.to_le_bytes()),
+        PropertyValue::Int(value) => buf.extend(value.to_le_bytes()),
+        PropertyValue::Int64(value) => buf.extend(value.to_le_bytes()),
+        PropertyValue::UInt16(value) => buf.extend(value.to_le_bytes()),
+        PropertyValue::UInt32(value) => buf.extend(value.to_le_bytes()),
+        PropertyValue::UInt64(value) => buf.extend(value.to_le_bytes()),
+        PropertyValue::Float(value) => buf.extend(value.to_le_bytes()),
+        PropertyValue::Double(value) => buf.extend(value.to_le_bytes()),
+        PropertyValue::Str(value) | PropertyValue::Name(value) | PropertyValue::Object(value) => write_string(buf, value),
+        PropertyValue::Byte { value: ByteValue::Byte(value), .. } => buf.push(*value),
+        PropertyValue::Byte { value: ByteValue::Name(value), .. } => write_string(buf, value),
+        PropertyValue::Enum { value, .. } => write_string(buf, value),
+        PropertyValue::Struct { value, .. } => write_struct_value(buf, value),
+        PropertyValue::Array { value, .. } => write_array(buf, value),
+        PropertyValue::Set { value, .. } => write_set(buf, value),
+        PropertyValue::Map { value, .. } => write_map(buf, value),
+        PropertyValue::Other { data, .. } => buf.extend(data)
+    }
+    patch_size(buf, size_pos, start);
+}
+
+fn write_properties(buf: &mut Vec<u8>, properties: &[Property]) {
+    for property in properties {
+        write_property(buf, property);
+    }
+    write_string(buf, NONE);
+}
+
+fn write_header(buf: &mut Vec<u8>, header: &SaveHeader) {
+    buf.extend(GVAS_MAGIC);
+    buf.extend(header.save_game_version.to_le_bytes());
+    buf.extend(header.package_version.to_le_bytes());
+    if let Some(package_version_ue5) = header.package_version_ue5 {
+        buf.extend(package_version_ue5.to_le_bytes());
+    }
+
+    let engine_version = &header.engine_version;
+    buf.extend(engine_version.major.to_le_bytes());
+    buf.extend(engine_version.minor.to_le_bytes());
+    buf.extend(engine_version.patch.to_le_bytes());
+    buf.extend(engine_version.changelist.to_le_bytes());
+    write_string(buf, &engine_version.branch);
+
+    buf.extend(header.custom_version_format.to_le_bytes());
+    buf.extend((header.custom_versions.len() as u32).to_le_bytes());
+    for custom_version in &header.custom_versions {
+        buf.extend(custom_version.key.0);
+        buf.extend(custom_version.version.to_le_bytes());
+    }
+
+    write_string(buf, &header.save_game_class);
+}
+
+fn original_header(header: &SaveHeader, bytes: &[u8]) -> Option<Original<SaveHeader>> {
+    let mut written = Vec::new();
+    write_header(&mut written, header);
+    if written == bytes {
+        None
+    } else {
+        Some(Original { value: header.clone(), bytes: bytes.to_vec() })
+    }
+}
+
+impl SaveHeader {
+    pub fn read(buf: &[u8]) -> Result<SaveHeader, SaveError> {
+        read_header(&mut Reader::new(buf, 0))
+    }
+}
+
+impl Gvas {
+    pub fn read(buf: &[u8]) -> Result<Gvas, SaveError> {
+        let mut r = Reader::new(buf, 0);
+        let header = read_header(&mut r)?;
+        let original_header = original_header(&header, &buf[..r.pos]);
+        let properties = read_properties(&mut r)?;
+        let trailer = r.rest().to_vec();
+
+        Ok(Gvas { header, properties, trailer, original_header })
+    }
+
+    pub fn write(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self.original_header.as_ref().filter(|original| original.value == self.header) {
+            Some(original) => buf.extend(&original.bytes),
+            None => write_header(&mut buf, &self.header)
+        }
+        write_properties(&mut buf, &self.properties);
+        buf.extend(&self.trailer);
+        buf
+    }
+}
+
+impl StructValue {
+    pub fn properties(&self) -> Option<&[Property]> {
+        match self {
+            StructValue::Properties(properties) => Some(properties),
+            _ => None
+        }
+    }
+
+    pub fn properties_mut(&mut self) -> Option<&mut Vec<Property>> {
+        match self {
+            StructValue::Properties(properties) => Some(properties),
+            _ => None
+        }
+    }
+
+    pub fn as_guid(&self) -> Option<Guid> {
+        match self {
+            StructValue::Guid(guid) => Some(*guid),
+            _ => None
+        }
+    }
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(value) => Some(value),
+            _ => None
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            Value::Int(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    pub fn as_guid(&self) -> Option<Guid> {
+        match self {
+            Value::Struct(value) => value.as_guid(),
+            _ => None
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f32> {
+        match self {
+            Value::Float(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    pub fn properties(&self) -> Option<&[Property]> {
+        match self {
+            Value::Struct(value) => value.properties(),
+            _ => None
+        }
+    }
+
+    pub fn properties_mut(&mut self) -> Option<&mut Vec<Property>> {
+        match self {
+            Value::Struct(value) => value.properties_mut(),
+            _ => None
+        }
+    }
+}
+
+impl PropertyValue {
+    pub fn type_name(&self) -> &str {
+        match self {
+            PropertyValue::Bool(_) => "BoolProperty",
+            PropertyValue::Int8(_) => "Int8Property",
+            PropertyValue::Int16(_) => "Int16Property",
+            PropertyValue::Int(_) => "IntProperty",
+            PropertyValue::Int64(_) => "Int64Property",
+            PropertyValue::UInt16(_) => "UInt16Property",
+            PropertyValue::UInt32(_) => "UInt32Property",
+            PropertyValue::UInt64(_) => "UInt64Property",
+            PropertyValue::Float(_) => "FloatProperty",
+            PropertyValue::Double(_) => "DoubleProperty",
+            PropertyValue::Str(_) => "StrProperty",
+            PropertyValue::Name(_) => "NameProperty",
+            PropertyValue::Object(_) => "ObjectProperty",
+            PropertyValue::Byte { .. } => "ByteProperty",
+            PropertyValue::Enum { .. } => "EnumProperty",
+            PropertyValue::Struct { .. } => "StructProperty",
+            PropertyValue::Array { .. } => "ArrayProperty",
+            PropertyValue::Set { .. } => "SetProperty",
+            PropertyValue::Map { .. } => "MapProperty",
+            PropertyValue::Other { type_name, .. } => type_name
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            PropertyValue::Bool(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            PropertyValue::Int(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    pub fn as_int_mut(&mut self) -> Option<&mut i32> {
+        match self {
+            PropertyValue::Int(value) => Some(value),
+            _ => None
+        }
+    }
+
+    pub fn as_guid(&self) -> Option<Guid> {
+        match self {
+            PropertyValue::Struct { value, .. } => value.as_guid(),
+            _ => None
+        }
+    }
+
+    pub fn as_guid_array(&self) -> Option<Vec<Guid>> {
+        match self {
+            PropertyValue::Array { value: ArrayValue::Structs { elements, .. }, .. } => elements.iter().map(StructValue::as_guid).collect(),
+            _ => None
+        }
+    }
+
+    pub fn as_int_array(&self) -> Option<Vec<i32>> {
+        match self {
+            PropertyValue::Array { value: ArrayValue::Values(values), .. } => values.iter().map(|value| match value {
+                Value::Int(value) => Some(*value),
+                _ => None
+            }).collect(),
+            _ => None
+        }
+    }
+
+    pub fn as_struct_array(&self) -> Option<&[StructValue]> {
+        match self {
+            PropertyValue::Array { value: ArrayValue::Structs { elements, .. }, .. } => Some(elements),
+            _ => None
+        }
+    }
+
+    pub fn as_struct_array_mut(&mut self) -> Option<&mut Vec<StructValue>> {
+        match self {
+            PropertyValue::Array { value: ArrayValue::Structs { elements, .. }, .. } => Some(elements),
+            _ => None
+        }
+    }
+
+    pub fn map(key_type: &str, value_type: &str, entries: Vec<(Value, Value)>) -> PropertyValue {
+        PropertyValue::Map {
+            key_type: key_type.to_string(),
+            value_type: value_type.to_string(),
+            value: MapValue::Entries { removed: Vec::new(), entries }
+        }
+    }
+
+    pub fn map_entries(&self) -> Option<&[(Value, Value)]> {
+        match self {
+            PropertyValue::Map { value: MapValue::Entries { entries, .. }, .. } => Some(entries),
+            _ => None
+        }
+    }
+
+    pub fn map_entries_mut(&mut self) -> Option<&mut Vec<(Value, Value)>> {
+        match self {
+            PropertyValue::Map { value: MapValue::Entries { entries, .. }, .. } => Some(entries),
+            _ => None
+        }
+    }
+
+    pub fn guid(guid: Guid) -> PropertyValue {
+        PropertyValue::Struct {
+            struct_type: "Guid".to_string(),
+            struct_guid: Guid::default(),
+            value: StructValue::Guid(guid)
+        }
+    }
+
+    pub fn int_array(values: &[i32]) -> PropertyValue {
+        PropertyValue::Array {
+            inner_type: "IntProperty".to_string(),
+            value: ArrayValue::Values(values.iter().map(|value| Value::Int(*value)).collect())
+        }
+    }
+
+    pub fn struct_array(name: &str, struct_type: &str, elements: Vec<StructValue>) -> PropertyValue {
+        PropertyValue::Array {
+            inner_type: "StructProperty".to_string(),
+            value: ArrayValue::Structs {
+                name: name.to_string(),
+                struct_type: struct_type.to_string(),
+                struct_guid: Guid::default(),
+                elements
+            }
+        }
+    }
+
+    pub fn guid_array(name: &str, guids: &[Guid]) -> PropertyValue {
+        PropertyValue::struct_array(name, "Guid", guids.iter().map(|guid| StructValue::Guid(*guid)).collect())
+    }
+
+    // Property lists nested directly below this value
+    fn children(&self) -> Vec<&[Property]> {
+        match self {
+            PropertyValue::Struct { value, .. } => value.properties().into_iter().collect(),
+            PropertyValue::Array { value: ArrayValue::Structs { elements, .. }, .. } => elements.iter().filter_map(StructValue::properties).collect(),
+            PropertyValue::Array { value: ArrayValue::Values(values), .. } => values.iter().filter_map(Value::properties).collect(),
+            PropertyValue::Set { value: SetValue::Elements { elements, .. }, .. } => elements.iter().filter_map(Value::properties).collect(),
+            PropertyValue::Map { value: MapValue::Entries { entries, .. }, .. } => entries.iter()
+                .flat_map(|(k, v)| vec![k, v])
+                .filter_map(Value::properties)
+                .collect(),
+            _ => Vec::new()
+        }
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Vec<Property>> {
+        match self {
+            PropertyValue::Struct { value, .. } => value.properties_mut().into_iter().collect(),
+            PropertyValue::Array { value: ArrayValue::Structs { elements, .. }, .. } => elements.iter_mut().filter_map(StructValue::properties_mut).collect(),
+            PropertyValue::Array { value: ArrayValue::Values(values), .. } => values.iter_mut().filter_map(Value::properties_mut).collect(),
+            PropertyValue::Set { value: SetValue::Elements { elements, .. }, .. } => elements.iter_mut().filter_map(Value::properties_mut).collect(),
+            PropertyValue::Map { value: MapValue::Entries { entries, .. }, .. } => entries.iter_mut()
+                .flat_map(|(k, v)| vec![k, v])
+                .filter_map(Value::properties_mut)
+                .collect(),
+            _ => Vec::new()
+        }
+    }
+}
+
+impl Property {
+    pub fn new(name: &str, value: PropertyValue) -> Property {
+        Property { name: name.to_string(), index: 0, guid: None, value, original: None }
+    }
+
+    fn keeping_original(mut self, bytes: &[u8]) -> Property {
+        let mut written = Vec::new();
+        write_property(&mut written, &self);
+        if written != bytes {
+            self.original = Some(Box::new(Original { value: self.clone(), bytes: bytes.to_vec() }));
+        }
+        self
+    }
+
+    fn same_contents(&self, other: &Property) -> bool {
+        self.name == other.name && self.index == other.index && self.guid == other.guid && self.value == other.value
+    }
+}
+
+// Replaces the value of `name` in this list, the property gets appended when it isn't there yet
+pub fn set_property(properties: &mut Vec<Property>, name: &str, value: PropertyValue) {
+    match properties.iter_mut().find(|p| p.name == name) {
+        Some(property) => property.value = value,
+        None => properties.push(Property::new(name, value))
+    }
+}
+
+// Brings keyed entries in line with `items`. Entries no item has the key of are dropped, the
+// others are matched up by the key of the entry `new` would create for the item, or appended
+// when there's none. `update` then writes the item into its entry, whatever else the entry
+// holds and the order of the entries are left alone. Entries without a key are kept
+fn upsert_keyed<E, T, K: PartialEq>(
+    entries: &mut Vec<E>,
+    items: &[T],
+    key: impl Fn(&E) -> Option<K>,
+    new: impl Fn(&T) -> E,
+    mut update: impl FnMut(&mut E, &T) -> Result<(), SaveError>
+) -> Result<(), SaveError> {
+    let item_keys: Vec<Option<K>> = items.iter().map(|item| key(&new(item))).collect();
+    entries.retain(|entry| key(entry).is_none_or(|k| item_keys.contains(&Some(k))));
+
+    for (item, item_key) in items.iter().zip(&item_keys) {
+        let pos = match entries.iter().position(|entry| item_key.is_some() && key(entry) == *item_key) {
+            Some(pos) => pos,
+            None => {
+                entries.push(new(item));
+                entries.len() - 1
+            }
+        };
+        update(&mut entries[pos], item)?;
+    }
+    Ok(())
+}
+
+// upsert_keyed on the struct array `name`, which gets added when it's missing and there are items
+pub fn upsert_struct_array<T, K: PartialEq>(
+    properties: &mut Vec<Property>,
+    name: &str,
+    struct_type: &str,
+    items: &[T],
+    key: impl Fn(&[Property]) -> Option<K>,
+    new: impl Fn(&T) -> Vec<Property>,
+    mut update: impl FnMut(&mut Vec<Property>, &T) -> Result<(), SaveError>
+) -> Result<(), SaveError> {
+    let elements = match find_property_mut(properties, name) {
+        Some(e) => e.value.as_struct_array_mut().ok_or_else(|| SaveError::unexpected_type(name, "an array of structs"))?,
+        None if items.is_empty() => return Ok(()),
+        None => {
+            properties.push(Property::new(name, PropertyValue::struct_array(name, struct_type, Vec::new())));
+            properties.last_mut().and_then(|p| p.value.as_struct_array_mut()).ok_or_else(|| SaveError::missing(name))?
+        }
+    };
+
+    upsert_keyed(elements, items,
+        |element| element.properties().and_then(&key),
+        |item| StructValue::Properties(new(item)),
+        |element, item| update(element.properties_mut().ok_or_else(|| SaveError::unexpected_type(name, "an array of structs"))?, item))
+}
+
+// upsert_keyed on the map `name`, which gets added with `types` for its keys and values when
+// it's missing and there are items
+pub fn upsert_map<T, K: PartialEq>(
+    properties: &mut Vec<Property>,
+    name: &str,
+    types: (&str, &str),
+    items: &[T],
+    key: impl Fn(&Value) -> Option<K>,
+    new: impl Fn(&T) -> (Value, Value),
+    mut update: impl FnMut(&mut Value, &T) -> Result<(), SaveError>
+) -> Result<(), SaveError> {
+    let entries = match find_property_mut(properties, name) {
+        Some(e) => e.value.map_entries_mut().ok_or_else(|| SaveError::unexpected_type(name, "a map"))?,
+        None if items.is_empty() => return Ok(()),
+        None => {
+            properties.push(Property::new(name, PropertyValue::map(types.0, types.1, Vec::new())));
+            properties.last_mut().and_then(|p| p.value.map_entries_mut()).ok_or_else(|| SaveError::missing(name))?
+        }
+    };
+
+    upsert_keyed(entries, items, |(k, _)| key(k), new, |(_, v), item| update(v, item))
+}
+
+// Searches the whole tree breadth first, so shallower matches win over nested ones
+pub fn find_property<'a>(properties: &'a [Property], name: &str) -> Option<&'a Property> {
+    find_property_list(properties, &|list| list.iter().any(|p| p.name == name))?
+        .iter()
+        .find(|p| p.name == name)
+}
+
+pub fn find_property_mut<'a>(properties: &'a mut Vec<Property>, name: &str) -> Option<&'a mut Property> {
+    find_property_list_mut(properties, &|list| list.iter().any(|p| p.name == name))?
+        .iter_mut()
+        .find(|p| p.name == name)
+}
+
+// Each step is the index of a property and of the child list below it, the mutable
+// search replays it since a queue of mutable borrows can't be kept around
+fn find_property_list_path(properties: &[Property], pred: &dyn Fn(&[Property]) -> bool) -> Option<Vec<(usize, usize)>> {
+    let mut queue = VecDeque::from(vec![(properties, Vec::new())]);
+    while let Some((list, path)) = queue.pop_front() {
+        if pred(list) {
+            return Some(path);
+        }
+
+        for (i, property) in list.iter().enumerate() {
+            for (j, child) in property.value.children().into_iter().enumerate() {
+                let mut child_path = path.clone();
+                child_path.push((i, j));
+                queue.push_back((child, child_path));
+            }
+        }
+    }
+    None
+}
+
+pub fn find_property_list<'a>(properties: &'a [Property], pred: &dyn Fn(&[Property]) -> bool) -> Option<&'a [Property]> {
+    let mut list = properties;
+    for (i, j) in find_property_list_path(properties, pred)? {
+        list = list[i].value.children().into_iter().nth(j)?;
+    }
+    Some(list)
+}
+
+pub fn find_property_list_mut<'a>(properties: &'a mut Vec<Property>, pred: &dyn Fn(&[Property]) -> bool) -> Option<&'a mut Vec<Property>> {
+    let path = find_property_list_path(properties, pred)?;
+    let mut list = properties;
+    for (i, j) in path {
+        let current = list;
+        list = current[i].value.children_mut().into_iter().nth(j)?;
+    }
+    Some(list)
+}
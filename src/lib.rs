@@ -1,10 +1,42 @@
+pub mod error;
+pub mod gvas;
+
 pub mod deep_rock_galactic {
-    use std::{array::TryFromSliceError, collections::HashMap, convert::TryInto, fs::File, io::{Write}};
-    use std::error::Error;
+    use std::{collections::{HashMap, HashSet}, convert::TryFrom, fs::{self, File}, io::{Read, Write}, path::Path};
     use serde::{Serialize, Deserialize};
-    use memchr::memmem;
+    use crate::gvas::{self, Guid, Gvas, Property, PropertyValue, StructValue, Value};
+    pub use crate::error::SaveError;
+    pub use crate::gvas::{CustomVersion, EngineVersion, SaveHeader};
+
+    mod backup;
+    mod forge;
+    mod milestones;
+    mod perks;
+    mod planner;
+    mod progression;
+    mod resources;
+    mod seasons;
+    mod stats;
+    mod trading;
+    mod vanity;
+    mod version;
+    mod weapons;
+    pub use backup::{Backup, BackupOptions, list_backups, restore_backup};
+    pub use forge::{FORGE_XP_PER_ITEM, FORGE_XP_PER_LEVEL};
+    pub use milestones::{Assignment, Assignments};
+    pub use perks::{EquippedPerks, Perk, PerkCatalog, PerkKind, Perks, UnlockedPerk};
+    pub use planner::{ForgePlan, ForgePriorities};
+    pub use progression::{MAX_LEVEL, PROMOTION_PERK_POINTS, promotion_title, xp_for_level};
+    pub use resources::{ResourceRegistry, Resources};
+    pub use seasons::{MAX_SEASON_LEVEL, SEASON_XP_PER_LEVEL, SeasonProgress};
+    pub use stats::MissionStats;
+    pub use trading::{Mineral, Trade, TradeRates};
+    pub use vanity::{Vanity, VanityCatalog, VanityItem, VanityKind};
+    pub use version::VersionWarning;
+    pub use weapons::{Maintenance, Upgrade, Weapon, WeaponCatalog, WeaponLoadout, WeaponUpgrades};
+    use resources::{BISMOR, ENOR, JADIZ, CROPPA, MAGNITE, UMANITE, YEAST, STARCH, BARLEY, MALT, ERROR_CORES, BLANK_CORES};
     
-    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
     pub struct Cost {
         pub credits: u32,
         pub bismor: u32,
@@ -15,24 +47,61 @@ pub mod deep_rock_galactic {
         pub umanite: u32    
     }
     
-    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub enum DwarfClass {
+        Engineer,
+        Scout,
+        Driller,
+        Gunner
+    }
+
+    impl DwarfClass {
+        pub const ALL: [DwarfClass; 4] = [DwarfClass::Engineer, DwarfClass::Scout, DwarfClass::Driller, DwarfClass::Gunner];
+
+        pub fn name(self) -> &'static str {
+            match self {
+                DwarfClass::Engineer => "Engineer",
+                DwarfClass::Scout => "Scout",
+                DwarfClass::Driller => "Driller",
+                DwarfClass::Gunner => "Gunner"
+            }
+        }
+
+        fn id(self) -> Guid {
+            Guid(match self {
+                DwarfClass::Engineer => ENGINEER,
+                DwarfClass::Scout => SCOUT,
+                DwarfClass::Driller => DRILLER,
+                DwarfClass::Gunner => GUNNER
+            })
+        }
+    }
+
+    impl std::fmt::Display for DwarfClass {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.name())
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct ClassProgress {
+        pub xp: u32,
+        pub promotions: u32
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
     
     pub enum OverclockState {
         Forged,
         Unforged,
+        #[default]
         Unacquired
     }
-    
-    impl Default for OverclockState {
-        fn default() -> OverclockState {
-            OverclockState::Unacquired
-        }
-    }
 
     
     #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
     pub struct Overclock {
-        pub class: String,
+        pub class: DwarfClass,
         pub weapon: String,
         pub name: String,
         pub cost: Cost,
@@ -42,7 +111,7 @@ pub mod deep_rock_galactic {
 
     #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
     pub struct Cosmetic {
-        pub class: String,
+        pub class: DwarfClass,
         pub name: String,
         pub cost: Cost,
         #[serde(skip_deserializing)]
@@ -114,336 +183,437 @@ pub mod deep_rock_galactic {
         
     #[derive(Debug, Clone, PartialEq)]
     pub struct SaveFile {
-        pub eng_xp: u32,
-        pub scout_xp: u32,
-    
-        pub drill_xp: u32,
-        pub gun_xp: u32,
-        pub eng_num_promo: u32,
-        pub scout_num_promo: u32,
-        pub drill_num_promo: u32,
-        pub gun_num_promo: u32,
+        pub classes: HashMap<DwarfClass, ClassProgress>,
     
         pub credits: u32,
         pub perkpoints: u32,
+        pub scrip: u32,
+        pub seasons: Vec<SeasonProgress>,
+        pub perks: Perks,
+        pub weapons: WeaponUpgrades,
+        pub vanity: Vanity,
+        pub milestones: HashMap<String, u32>,
+        pub assignments: Assignments,
     
         pub blank_cores: f32,
         pub error_cores: f32,
     
         pub minerals: Minerals,
         pub brewing: Brewing,
+        pub resources: Resources,
     
         pub matrix_cores: MatrixCores,
     
-        buf: Vec<u8>,
-    
-        eng_xp_pos: usize,
-        scout_xp_pos: usize,
-        drill_xp_pos: usize,
-        gun_xp_pos: usize
+        forge_xp: u32,
+        forge_xp_forged: u32,
+        gvas: Gvas,
+        version_warnings: Vec<VersionWarning>
     }
 
-    // are those magic numbers? yes
-    // do they work? yes
-    const EN_MARKER: [u8; 22] = [0x85, 0xEF, 0x62, 0x6C, 0x65, 0xF1, 0x02, 0x4A, 0x8D, 0xFE, 0xB5, 0xD0, 0xF3, 0x90, 0x9D, 0x2E, 0x03, 0x00, 0x00, 0x00, 0x58, 0x50];
-    const SC_MARKER: [u8; 22] = [0x30, 0xD8, 0xEA, 0x17, 0xD8, 0xFB, 0xBA, 0x4C, 0x95, 0x30, 0x6D, 0xE9, 0x65, 0x5C, 0x2F, 0x8C, 0x03, 0x00, 0x00, 0x00, 0x58, 0x50];
-    const DR_MARKER: [u8; 22] = [0x9E, 0xDD, 0x56, 0xF1, 0xEE, 0xBC, 0xC5, 0x48, 0x8D, 0x5B, 0x5E, 0x5B, 0x80, 0xB6, 0x2D, 0xB4, 0x03, 0x00, 0x00, 0x00, 0x58, 0x50];
-    const GU_MARKER: [u8; 22] = [0xAE, 0x56, 0xE1, 0x80, 0xFE, 0xC0, 0xC4, 0x4D, 0x96, 0xFA, 0x29, 0xC2, 0x83, 0x66, 0xB9, 0x7B, 0x03, 0x00, 0x00, 0x00, 0x58, 0x50];
-    const XP_OFFSET: usize = 48;
-    const NUM_PROMO_OFFSET: usize = 108;
-    const CREDITS_OFFSET: usize = 33;
-    const PERK_POINTS_OFFSET: usize = 36;
-    const GUID_LENGTH: usize = 16;
-    const MATRIX_CORES_LIST_OFFSET: usize = 141;
-
-    //Resources
-    //Minerals
-    const      BISMOR: [u8; 16] = [0xAF, 0x0D, 0xC4, 0xFE, 0x83, 0x61, 0xBB, 0x48, 0xB3, 0x2C, 0x92, 0xCC, 0x97, 0xE2, 0x1D, 0xE7];
-    const        ENOR: [u8; 16] = [0x48, 0x8D, 0x05, 0x14, 0x6F, 0x5F, 0x75, 0x4B, 0xA3, 0xD4, 0x61, 0x0D, 0x08, 0xC0, 0x60, 0x3E];
-    const       JADIZ: [u8; 16] = [0x22, 0xBC, 0x4F, 0x7D, 0x07, 0xD1, 0x3E, 0x43, 0xBF, 0xCA, 0x81, 0xBD, 0x9C, 0x14, 0xB1, 0xAF];
-    const      CROPPA: [u8; 16] = [0x8A, 0xA7, 0xFB, 0x43, 0x29, 0x3A, 0x0B, 0x49, 0xB8, 0xBE, 0x42, 0xFF, 0xE0, 0x68, 0xA4, 0x4C];
-    const     MAGNITE: [u8; 16] = [0xAA, 0xDE, 0xD8, 0x76, 0x6C, 0x22, 0x7D, 0x40, 0x80, 0x32, 0xAF, 0xD1, 0x8D, 0x63, 0x56, 0x1E];
-    const     UMANITE: [u8; 16] = [0x5F, 0x2B, 0xCF, 0x83, 0x47, 0x76, 0x0A, 0x42, 0xA2, 0x3B, 0x6E, 0xDC, 0x07, 0xC0, 0x94, 0x1D];
-
-    //Brewing
-    const       YEAST: [u8; 16] = [0x07, 0x85, 0x48, 0xB9, 0x32, 0x32, 0xC0, 0x40, 0x85, 0xF8, 0x92, 0xE0, 0x84, 0xA7, 0x41, 0x00];
-    const      STARCH: [u8; 16] = [0x72, 0x31, 0x22, 0x04, 0xE2, 0x87, 0xBC, 0x41, 0x81, 0x55, 0x40, 0xA0, 0xCF, 0x88, 0x12, 0x80];
-    const      BARLEY: [u8; 16] = [0x22, 0xDA, 0xA7, 0x57, 0xAD, 0x7A, 0x80, 0x49, 0x89, 0x1B, 0x17, 0xED, 0xCC, 0x2F, 0xE0, 0x98]; 
-    const        MALT: [u8; 16] = [0x41, 0xEA, 0x55, 0x0C, 0x1D, 0x46, 0xC5, 0x4B, 0xBE, 0x2E, 0x9C, 0xA5, 0xA7, 0xAC, 0xCB, 0x06];
-
-    // Misc?
-    const ERROR_CORES: [u8; 16] = [0x58, 0x28, 0x65, 0x2C, 0x9A, 0x5D, 0xE8, 0x45, 0xA9, 0xE2, 0xE1, 0xB8, 0xB4, 0x63, 0xC5, 0x16];
-    const BLANK_CORES: [u8; 16] = [0xA1, 0x0C, 0xB2, 0x85, 0x38, 0x71, 0xFB, 0x49, 0x9A, 0xC8, 0x54, 0xA1, 0xCD, 0xE2, 0x20, 0x2C];
-
-    //Matrix cores
-    const MATRIX_CORES_UNFORGED_HEADER: [u8; 66] = [0x10, 0x00, 0x00, 0x00, 0x4F, 0x77, 0x6E, 0x65, 0x64, 0x53, 0x63, 0x68, 0x65, 0x6D, 0x61, 0x74, 0x69, 0x63, 0x73, 0x00, 0x0E, 0x00, 0x00, 0x00, 0x41, 0x72, 0x72, 0x61, 0x79, 0x50, 0x72, 0x6F, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x6D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x53, 0x74, 0x72, 0x75, 0x63, 0x74, 0x50, 0x72, 0x6F, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x00];
-    const MATRIX_CORES_UNFORGED_FOOTER: [u8; 73] = [0x10, 0x00, 0x00, 0x00, 0x4F, 0x77, 0x6E, 0x65, 0x64, 0x53, 0x63, 0x68, 0x65, 0x6D, 0x61, 0x74, 0x69, 0x63, 0x73, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x53, 0x74, 0x72, 0x75, 0x63, 0x74, 0x50, 0x72, 0x6F, 0x70, 0x65, 0x72, 0x74, 0x79, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x47, 0x75, 0x69, 0x64, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
-
+    // Class ids
+    const  ENGINEER: [u8; 16] = [0x85, 0xEF, 0x62, 0x6C, 0x65, 0xF1, 0x02, 0x4A, 0x8D, 0xFE, 0xB5, 0xD0, 0xF3, 0x90, 0x9D, 0x2E];
+    const     SCOUT: [u8; 16] = [0x30, 0xD8, 0xEA, 0x17, 0xD8, 0xFB, 0xBA, 0x4C, 0x95, 0x30, 0x6D, 0xE9, 0x65, 0x5C, 0x2F, 0x8C];
+    const   DRILLER: [u8; 16] = [0x9E, 0xDD, 0x56, 0xF1, 0xEE, 0xBC, 0xC5, 0x48, 0x8D, 0x5B, 0x5E, 0x5B, 0x80, 0xB6, 0x2D, 0xB4];
+    const    GUNNER: [u8; 16] = [0xAE, 0x56, 0xE1, 0x80, 0xFE, 0xC0, 0xC4, 0x4D, 0x96, 0xFA, 0x29, 0xC2, 0x83, 0x66, 0xB9, 0x7B];
+
+    // Property names
+    const XP: &str = "XP";
+    const NUM_PROMOTIONS: &str = "NumPromotions";
+    const CREDITS: &str = "Credits";
+    const PERK_POINTS: &str = "PerkPoints";
+    const SCRIP: &str = "Scrip";
+    const FORGE_MASTER_XP: &str = "ForgeMasterXP";
+    const OWNED_RESOURCES: &str = "OwnedResources";
+    const FORGED_SCHEMATICS: &str = "ForgedSchematics";
+    const OWNED_SCHEMATICS: &str = "OwnedSchematics";
 
     impl SaveFile {
 
-        fn get_xp_offset(buf: &[u8], marker: &[u8]) -> Option<usize> {
-            memmem::find_iter(buf, marker).next().and_then(|f| Some(f + XP_OFFSET))
+        // The class id is stored as a guid right in front of the class XP
+        fn is_class_properties(list: &[Property], class_id: Guid) -> bool {
+            list.windows(2).any(|w| w[0].value.as_guid() == Some(class_id) && w[1].name == XP)
         }
 
-        fn get_xp(buf: &[u8], offset: usize) -> Result<u32, TryFromSliceError> {
-            let eng_slice = buf[offset..offset+4].try_into()?;
-            Ok(u32::from_le_bytes(eng_slice))
+        fn get_class_progress(properties: &[Property], class: DwarfClass) -> Result<ClassProgress, SaveError> {
+            let class_properties = gvas::find_property_list(properties, &|list| SaveFile::is_class_properties(list, class.id()))
+                .ok_or_else(|| SaveError::missing(&format!("{} XP", class)))?;
+
+            let xp = class_properties.iter().find(|p| p.name == XP)
+                .and_then(|p| p.value.as_int())
+                .and_then(|xp| u32::try_from(xp).ok())
+                .ok_or_else(|| SaveError::unexpected_type(&format!("{} XP", class), "a non-negative IntProperty"))?;
+            let promotions = match class_properties.iter().find(|p| p.name == NUM_PROMOTIONS) {
+                Some(e) => e.value.as_int().and_then(|promotions| u32::try_from(promotions).ok())
+                    .ok_or_else(|| SaveError::unexpected_type(&format!("{} {}", class, NUM_PROMOTIONS), "a non-negative IntProperty"))?,
+                None => 0
+            };
+            Ok(ClassProgress { xp, promotions })
         }
 
-        fn set_val(&mut self, offset: usize, val: u32) {
-            let bytes = val.to_le_bytes();
-            self.buf[offset] = bytes[0];
-            self.buf[offset + 1] = bytes[1];
-            self.buf[offset + 2] = bytes[2];
-            self.buf[offset + 3] = bytes[3];
+        // The save holds signed ints, anything past their range is capped
+        fn to_save_int(val: u32) -> i32 {
+            i32::try_from(val).unwrap_or(i32::MAX)
         }
 
-        fn get_resources_pos(buf: &[u8]) -> Option<usize> {
-            memmem::find_iter(buf, &String::from("OwnedResources").into_bytes()).next()
+        fn set_class_progress(properties: &mut Vec<Property>, class: DwarfClass, progress: &ClassProgress) -> Result<(), SaveError> {
+            let class_properties = gvas::find_property_list_mut(properties, &|list| SaveFile::is_class_properties(list, class.id()))
+                .ok_or_else(|| SaveError::missing(&format!("{} XP", class)))?;
+
+            let xp_pos = class_properties.iter().position(|p| p.name == XP).ok_or_else(|| SaveError::missing(&format!("{} XP", class)))?;
+            *class_properties[xp_pos].value.as_int_mut()
+                .ok_or_else(|| SaveError::unexpected_type(&format!("{} XP", class), "an IntProperty"))? = SaveFile::to_save_int(progress.xp);
+
+            match class_properties.iter_mut().find(|p| p.name == NUM_PROMOTIONS) {
+                Some(e) => *e.value.as_int_mut()
+                    .ok_or_else(|| SaveError::unexpected_type(&format!("{} {}", class, NUM_PROMOTIONS), "an IntProperty"))? = SaveFile::to_save_int(progress.promotions),
+                None if progress.promotions > 0 =>
+                    class_properties.insert(xp_pos + 1, Property::new(NUM_PROMOTIONS, PropertyValue::Int(SaveFile::to_save_int(progress.promotions)))),
+                None => {}
+            }
+            Ok(())
         }
 
-        fn get_resource_val(buf: &[u8], resources_pos: usize, resource_guid: &[u8]) -> Option<f32> {
-            let resource_pos = memmem::find_iter(&buf[resources_pos..buf.len()], resource_guid).next()? + GUID_LENGTH;
-            Some(f32::from_le_bytes(buf[resources_pos+resource_pos..resources_pos+resource_pos+4].try_into().ok()?))
+        fn get_int(properties: &[Property], name: &str) -> Result<u32, SaveError> {
+            let property = gvas::find_property(properties, name).ok_or_else(|| SaveError::missing(name))?;
+            property.value.as_int()
+                .and_then(|val| u32::try_from(val).ok())
+                .ok_or_else(|| SaveError::unexpected_type(name, "a non-negative IntProperty"))
         }
 
-        fn set_resource_val(buf: &mut [u8], resources_pos: usize, resource_guid: &[u8], val: f32) -> Option<()> {
-            let resource_pos = memmem::find_iter(&buf[resources_pos..buf.len()], resource_guid).next()? + GUID_LENGTH;
-            let bytes_val = val.to_le_bytes();
-            let off = resources_pos+resource_pos;
-            buf[off] = bytes_val[0];
-            buf[off+1] = bytes_val[1];
-            buf[off+2] = bytes_val[2];
-            buf[off+3] = bytes_val[3];
-            Some(())
+        fn set_int(properties: &mut Vec<Property>, name: &str, val: u32) -> Result<(), SaveError> {
+            let property = gvas::find_property_mut(properties, name).ok_or_else(|| SaveError::missing(name))?;
+            *property.value.as_int_mut().ok_or_else(|| SaveError::unexpected_type(name, "an IntProperty"))? = SaveFile::to_save_int(val);
+            Ok(())
         }
 
-        fn get_brewing(buf: &[u8]) -> Option<Brewing> {
-            let resources_pos = SaveFile::get_resources_pos(buf)?;
-            let yeast = SaveFile::get_resource_val(buf, resources_pos, &YEAST)?;
-            let starch = SaveFile::get_resource_val(buf, resources_pos, &STARCH)?;
-            let barley = SaveFile::get_resource_val(buf, resources_pos, &BARLEY)?;
-            let malt = SaveFile::get_resource_val(buf, resources_pos, &MALT)?;
+        fn get_optional_int(properties: &[Property], name: &str) -> Result<u32, SaveError> {
+            match gvas::find_property(properties, name) {
+                Some(_) => SaveFile::get_int(properties, name),
+                None => Ok(0)
+            }
+        }
 
-            Some(Brewing::new(yeast, starch, barley, malt))
+        fn set_optional_int(properties: &mut Vec<Property>, name: &str, val: u32) -> Result<(), SaveError> {
+            match gvas::find_property(properties, name) {
+                Some(_) => SaveFile::set_int(properties, name, val),
+                None if val != 0 => {
+                    properties.push(Property::new(name, PropertyValue::Int(SaveFile::to_save_int(val))));
+                    Ok(())
+                },
+                None => Ok(())
+            }
         }
 
-        fn get_minerals(buf: &[u8]) -> Option<Minerals> {
-            let resources_pos = SaveFile::get_resources_pos(buf)?;
-            let bismor = SaveFile::get_resource_val(buf, resources_pos, &BISMOR)?;
-            let enor = SaveFile::get_resource_val(buf, resources_pos, &ENOR)?;
-            let jadiz = SaveFile::get_resource_val(buf, resources_pos, &JADIZ)?;
-            let croppa = SaveFile::get_resource_val(buf, resources_pos, &CROPPA)?;
-            let magnite = SaveFile::get_resource_val(buf, resources_pos, &MAGNITE)?;
-            let umanite = SaveFile::get_resource_val(buf, resources_pos, &UMANITE)?;
-            Some(Minerals::new(bismor, enor, jadiz, croppa, magnite, umanite))
+        fn get_resources(properties: &[Property]) -> Result<HashMap<Guid, f32>, SaveError> {
+            gvas::find_property(properties, OWNED_RESOURCES)
+                .ok_or_else(|| SaveError::missing(OWNED_RESOURCES))?
+                .value
+                .map_entries()
+                .and_then(|entries| entries.iter().map(|(k, v)| Some((k.as_guid()?, v.as_float()?))).collect())
+                .ok_or_else(|| SaveError::unexpected_type(OWNED_RESOURCES, "a map of guids to floats"))
         }
 
-        fn get_credits_pos(buf: &[u8]) -> Option<usize> {
-            Some(memmem::find_iter(buf, &"Credits".to_string().into_bytes()).next()? + CREDITS_OFFSET)
+        fn get_resource_val(resources: &HashMap<Guid, f32>, resource_guid: &[u8; 16]) -> f32 {
+            resources.get(&Guid(*resource_guid)).copied().unwrap_or(0.0)
         }
 
-        fn get_perkpoints_pos(buf: &[u8]) -> Option<usize> {
-            memmem::find_iter(buf, &"PerkPoints".to_string().into_bytes()).next()
+        fn owned_resources_mut(properties: &mut Vec<Property>) -> Result<&mut Vec<(Value, Value)>, SaveError> {
+            gvas::find_property_mut(properties, OWNED_RESOURCES)
+                .ok_or_else(|| SaveError::missing(OWNED_RESOURCES))?
+                .value
+                .map_entries_mut()
+                .ok_or_else(|| SaveError::unexpected_type(OWNED_RESOURCES, "a map of guids to floats"))
         }
 
-        fn get_matrix_cores_start_pos(buf: &[u8]) -> Option<usize> {
-            memmem::find_iter(buf, &"ForgedSchematics".to_string().into_bytes()).next()
+        // Resources the player has never picked up have no entry, those are added
+        // unless they'd only hold a zero
+        fn set_resource_entry(entries: &mut Vec<(Value, Value)>, guid: Guid, val: f32) {
+            match entries.iter_mut().find(|(k, _)| k.as_guid() == Some(guid)) {
+                Some((_, value)) => *value = Value::Float(val),
+                None if val != 0.0 => entries.push((Value::Struct(StructValue::Guid(guid)), Value::Float(val))),
+                None => {}
+            }
         }
 
-        fn get_matrix_cores_end_pos(buf: &[u8]) -> Option<usize> {
-            memmem::find_iter(buf, &"bFirstSchematicMessageShown".to_string().into_bytes()).next()
+        fn set_resource_val(properties: &mut Vec<Property>, resource_guid: &[u8; 16], val: f32) -> Result<(), SaveError> {
+            SaveFile::set_resource_entry(SaveFile::owned_resources_mut(properties)?, Guid(*resource_guid), val);
+            Ok(())
         }
 
-        fn load_matrix_cores(buf: &[u8], guids: &str) -> Option<MatrixCores> {
-            let mut parsed_matrix_cores = serde_json::from_str::<MatrixCores>(guids).ok()?;
+        fn save_resources(&self, properties: &mut Vec<Property>) -> Result<(), SaveError> {
+            let entries = SaveFile::owned_resources_mut(properties)?;
 
-            let start_pos = SaveFile::get_matrix_cores_start_pos(buf)?;
-            let end_pos = SaveFile::get_matrix_cores_end_pos(buf)?;
+            // entries with a typed field are written from it afterwards
+            entries.retain(|(k, _)| k.as_guid().is_none_or(|guid| resources::has_typed_field(&guid) || self.resources.owned.contains_key(&guid)));
+            let mut owned: Vec<_> = self.resources.owned.iter().filter(|(guid, _)| !resources::has_typed_field(guid)).collect();
+            owned.sort_by_key(|(guid, _)| **guid);
+            for (guid, val) in owned {
+                SaveFile::set_resource_entry(entries, *guid, *val);
+            }
+            Ok(())
+        }
 
-            let data_slice = &buf[start_pos..end_pos];
-            
-            let num_forged = u32::from_le_bytes(data_slice[63..67].try_into().ok()?); // magic numbers yay
-            
-            for i in 0..num_forged {
-                let uuid = hex::encode(&data_slice[MATRIX_CORES_LIST_OFFSET+ (i*16) as usize ..MATRIX_CORES_LIST_OFFSET+ (i*16) as usize +16]).to_uppercase();
+        fn get_brewing(resources: &HashMap<Guid, f32>) -> Brewing {
+            let yeast = SaveFile::get_resource_val(resources, &YEAST);
+            let starch = SaveFile::get_resource_val(resources, &STARCH);
+            let barley = SaveFile::get_resource_val(resources, &BARLEY);
+            let malt = SaveFile::get_resource_val(resources, &MALT);
 
-                if let Some(overclock) = parsed_matrix_cores.overclocks.get_mut(&uuid) {
-                    overclock.state = OverclockState::Forged;
-                }
+            Brewing::new(yeast, starch, barley, malt)
+        }
 
-                if let Some(cosmetic) = parsed_matrix_cores.cosmetics.get_mut(&uuid) {
-                    cosmetic.state = OverclockState::Forged;
-                }
+        fn get_minerals(resources: &HashMap<Guid, f32>) -> Minerals {
+            let bismor = SaveFile::get_resource_val(resources, &BISMOR);
+            let enor = SaveFile::get_resource_val(resources, &ENOR);
+            let jadiz = SaveFile::get_resource_val(resources, &JADIZ);
+            let croppa = SaveFile::get_resource_val(resources, &CROPPA);
+            let magnite = SaveFile::get_resource_val(resources, &MAGNITE);
+            let umanite = SaveFile::get_resource_val(resources, &UMANITE);
+            Minerals::new(bismor, enor, jadiz, croppa, magnite, umanite)
+        }
+
+        fn set_matrix_core_state(matrix_cores: &mut MatrixCores, guid: &Guid, state: OverclockState) {
+            let uuid = guid.to_hex();
+
+            if let Some(overclock) = matrix_cores.overclocks.get_mut(&uuid) {
+                overclock.state = state.clone();
             }
 
-            if let Some(unforged_offset) = memmem::find_iter(data_slice, &"Owned".to_string().into_bytes()).next() {
-                let unforged_count_offset = unforged_offset + 62; // and more magic numbers idk even know where they came from
-                let num_unforged = u32::from_le_bytes(data_slice[unforged_count_offset..unforged_count_offset+4].try_into().ok()?);
-                let unforged_pos = unforged_count_offset + 77;
-                
-                for i in 0..num_unforged {
-                    let uuid = hex::encode(&data_slice[unforged_pos+ (i*16) as usize .. unforged_pos + (i*16) as usize + 16]).to_uppercase();
-
-                    if let Some(overclock) = parsed_matrix_cores.overclocks.get_mut(&uuid) {
-                        overclock.state = OverclockState::Unforged;
-                    }
-
-                    if let Some(cosmetic) = parsed_matrix_cores.cosmetics.get_mut(&uuid) {
-                        cosmetic.state = OverclockState::Unforged;
-                    }
+            if let Some(cosmetic) = matrix_cores.cosmetics.get_mut(&uuid) {
+                cosmetic.state = state;
+            }
+        }
+
+        fn get_guid_array(property: &Property) -> Result<Vec<Guid>, SaveError> {
+            property.value.as_guid_array().ok_or_else(|| SaveError::unexpected_type(&property.name, "an array of guids"))
+        }
+
+        // Lists the game only writes once something is in them
+        fn get_optional_guid_array(properties: &[Property], name: &str) -> Result<Vec<Guid>, SaveError> {
+            match gvas::find_property(properties, name) {
+                Some(e) => SaveFile::get_guid_array(e),
+                None => Ok(Vec::new())
+            }
+        }
+
+        fn set_optional_guid_array(properties: &mut Vec<Property>, name: &str, guids: &[Guid]) -> Result<(), SaveError> {
+            match gvas::find_property_mut(properties, name) {
+                Some(e) => *e.value.as_struct_array_mut().ok_or_else(|| SaveError::unexpected_type(name, "an array of guids"))? =
+                    guids.iter().map(|guid| StructValue::Guid(*guid)).collect(),
+                None if !guids.is_empty() => properties.push(Property::new(name, PropertyValue::guid_array(name, guids))),
+                None => {}
+            }
+            Ok(())
+        }
+
+        fn load_matrix_cores(properties: &[Property], guids: &str) -> Result<MatrixCores, SaveError> {
+            let mut parsed_matrix_cores = serde_json::from_str::<MatrixCores>(guids)?;
+
+            let forged = gvas::find_property(properties, FORGED_SCHEMATICS).ok_or_else(|| SaveError::missing(FORGED_SCHEMATICS))?;
+            for guid in &SaveFile::get_guid_array(forged)? {
+                SaveFile::set_matrix_core_state(&mut parsed_matrix_cores, guid, OverclockState::Forged);
+            }
+
+            if let Some(owned) = gvas::find_property(properties, OWNED_SCHEMATICS) {
+                for guid in &SaveFile::get_guid_array(owned)? {
+                    SaveFile::set_matrix_core_state(&mut parsed_matrix_cores, guid, OverclockState::Unforged);
                 }
             }
 
-            Some(parsed_matrix_cores)        
+            Ok(parsed_matrix_cores)        
         }
 
-        pub fn new(buf: &mut [u8], guids: &str) -> Option<Self> {
-            let eng_xp_pos = SaveFile::get_xp_offset(buf, &EN_MARKER)?;
-            let scout_xp_pos = SaveFile::get_xp_offset(buf, &SC_MARKER)?;
-            let drill_xp_pos = SaveFile::get_xp_offset(buf, &DR_MARKER)?;
-            let gun_xp_pos = SaveFile::get_xp_offset(buf, &GU_MARKER)?;
+        pub fn from_reader<R: Read>(mut reader: R, guids: &str) -> Result<Self, SaveError> {
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+            SaveFile::new(&buf, guids)
+        }
 
-            let eng_xp = SaveFile::get_xp(buf, eng_xp_pos).ok()?;
-            let scout_xp = SaveFile::get_xp(buf, scout_xp_pos).ok()?;
-            let drill_xp = SaveFile::get_xp(buf, drill_xp_pos).ok()?;
-            let gun_xp = SaveFile::get_xp(buf, gun_xp_pos).ok()?;
+        pub fn from_path<P: AsRef<Path>>(path: P, guids: &str) -> Result<Self, SaveError> {
+            SaveFile::new(&fs::read(path)?, guids)
+        }
 
-            let eng_num_promo = u32::from_le_bytes(buf[eng_xp_pos + NUM_PROMO_OFFSET..eng_xp_pos + NUM_PROMO_OFFSET + 4].try_into().ok()?);
-            let scout_num_promo = u32::from_le_bytes(buf[scout_xp_pos + NUM_PROMO_OFFSET..scout_xp_pos + NUM_PROMO_OFFSET + 4].try_into().ok()?);
-            let drill_num_promo = u32::from_le_bytes(buf[drill_xp_pos + NUM_PROMO_OFFSET..drill_xp_pos + NUM_PROMO_OFFSET + 4].try_into().ok()?);
-            let gun_num_promo = u32::from_le_bytes(buf[gun_xp_pos + NUM_PROMO_OFFSET..gun_xp_pos + NUM_PROMO_OFFSET + 4].try_into().ok()?);
-            
-            let credits_pos = SaveFile::get_credits_pos(buf)?;
-            let credits = u32::from_le_bytes(buf[credits_pos..credits_pos+4].try_into().ok()?);
+        pub fn new(buf: &[u8], guids: &str) -> Result<Self, SaveError> {
+            let gvas = Gvas::read(buf)?;
+            let version_warnings = version::check_header(&gvas.header)?;
+            let properties = &gvas.properties;
 
-            let perkpoints_pos = SaveFile::get_perkpoints_pos(buf);
-            let perkpoints = match perkpoints_pos {
-                Some(e) => u32::from_le_bytes(buf[e+PERK_POINTS_OFFSET..e+PERK_POINTS_OFFSET+4].try_into().ok()?),
-                None => 0
-            };
+            let classes = DwarfClass::ALL.iter()
+                .map(|class| Ok((*class, SaveFile::get_class_progress(properties, *class)?)))
+                .collect::<Result<HashMap<_, _>, SaveError>>()?;
+
+            let credits = SaveFile::get_int(properties, CREDITS)?;
+            let perkpoints = SaveFile::get_optional_int(properties, PERK_POINTS)?;
+            let scrip = SaveFile::get_optional_int(properties, SCRIP)?;
+            let seasons = seasons::load(properties)?;
 
-            let brewing = SaveFile::get_brewing(buf)?;
-            let minerals = SaveFile::get_minerals(buf)?;
+            let perks = Perks::load(properties)?;
+            let weapons = WeaponUpgrades::load(properties)?;
+            let vanity = Vanity::load(properties)?;
+            let milestones = milestones::load_milestones(properties)?;
+            let assignments = Assignments::load(properties)?;
 
-            let resources_pos = SaveFile::get_resources_pos(buf)?;
-            
-            let error_cores = SaveFile::get_resource_val(buf, resources_pos, &ERROR_CORES)?;
-            let blank_cores = SaveFile::get_resource_val(buf, resources_pos, &BLANK_CORES)?;
+            let resources = SaveFile::get_resources(properties)?;
+            let brewing = SaveFile::get_brewing(&resources);
+            let minerals = SaveFile::get_minerals(&resources);
 
+            let error_cores = SaveFile::get_resource_val(&resources, &ERROR_CORES);
+            let blank_cores = SaveFile::get_resource_val(&resources, &BLANK_CORES);
 
-            let matrix_cores = SaveFile::load_matrix_cores(buf, guids)?;
+            let matrix_cores = SaveFile::load_matrix_cores(properties, guids)?;
+            let forge_xp = SaveFile::get_optional_int(properties, FORGE_MASTER_XP)?;
+            let forge_xp_forged = forge::forged_count(&matrix_cores);
 
-            Some(SaveFile {
-                eng_xp,
-                eng_num_promo,
-                scout_xp,
-                scout_num_promo,
-                drill_xp,
-                drill_num_promo,
-                gun_xp,
-                gun_num_promo,
+            Ok(SaveFile {
+                classes,
                 credits,
                 perkpoints,
+                scrip,
+                seasons,
+                perks,
+                weapons,
+                vanity,
+                milestones,
+                assignments,
                 brewing,
                 minerals,
+                resources: Resources {
+                    owned: resources.into_iter().filter(|(guid, _)| !resources::has_typed_field(guid)).collect(),
+                    registry: ResourceRegistry::default()
+                },
                 error_cores,
                 blank_cores,
-                buf: buf.to_owned(),
                 matrix_cores,
-
-                eng_xp_pos,
-                gun_xp_pos,
-                scout_xp_pos,
-                drill_xp_pos
+                forge_xp,
+                forge_xp_forged,
+                gvas,
+                version_warnings
             })
         }
 
-        fn save_brewing(&mut self) -> Option<()> {
-            let resources_pos = SaveFile::get_resources_pos(&self.buf)?;
+        pub fn header(&self) -> &SaveHeader {
+            &self.gvas.header
+        }
 
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &YEAST, self.brewing.yeast)?;
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &STARCH, self.brewing.starch)?;
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &BARLEY, self.brewing.barley)?;
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &MALT, self.brewing.malt)?;
-            Some(())
+        pub fn version_warnings(&self) -> &[VersionWarning] {
+            &self.version_warnings
         }
 
-        fn save_minerals(&mut self) -> Option<()> {
-            let resources_pos = SaveFile::get_resources_pos(&self.buf)?;
+        fn save_brewing(&self, properties: &mut Vec<Property>) -> Result<(), SaveError> {
+            SaveFile::set_resource_val(properties, &YEAST, self.brewing.yeast)?;
+            SaveFile::set_resource_val(properties, &STARCH, self.brewing.starch)?;
+            SaveFile::set_resource_val(properties, &BARLEY, self.brewing.barley)?;
+            SaveFile::set_resource_val(properties, &MALT, self.brewing.malt)?;
+            Ok(())
+        }
 
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &BISMOR, self.minerals.bismor)?;
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &ENOR, self.minerals.enor)?;
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &JADIZ, self.minerals.jadiz)?;
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &CROPPA, self.minerals.croppa)?;
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &MAGNITE, self.minerals.magnite)?;
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &UMANITE, self.minerals.umanite)?;
+        fn save_minerals(&self, properties: &mut Vec<Property>) -> Result<(), SaveError> {
+            SaveFile::set_resource_val(properties, &BISMOR, self.minerals.bismor)?;
+            SaveFile::set_resource_val(properties, &ENOR, self.minerals.enor)?;
+            SaveFile::set_resource_val(properties, &JADIZ, self.minerals.jadiz)?;
+            SaveFile::set_resource_val(properties, &CROPPA, self.minerals.croppa)?;
+            SaveFile::set_resource_val(properties, &MAGNITE, self.minerals.magnite)?;
+            SaveFile::set_resource_val(properties, &UMANITE, self.minerals.umanite)?;
 
-            Some(())
+            Ok(())
         }
 
-        fn save_unforged_matrix_cores(&mut self, buf: &mut Vec<u8>) -> Option<()> {
-            let unforged_overclocks: Vec<(&String, &Overclock)> = self.matrix_cores.overclocks.iter().filter(|e| e.1.state == OverclockState::Unforged).collect();
-            let unforged_cosmetics: Vec<(&String, &Cosmetic)> = self.matrix_cores.cosmetics.iter().filter(|e| e.1.state == OverclockState::Unforged).collect();
-            let unforged_count = unforged_overclocks.len() as u32 + unforged_cosmetics.len() as u32;
-
-            if unforged_count > 0 {
-                buf.extend(&MATRIX_CORES_UNFORGED_HEADER);
-                buf.extend(unforged_count.to_le_bytes());
-                buf.extend(&MATRIX_CORES_UNFORGED_FOOTER);
+        fn matrix_cores_in_state(&self, state: OverclockState) -> Result<HashSet<Guid>, SaveError> {
+            let overclocks = self.matrix_cores.overclocks.iter().filter(|e| e.1.state == state).map(|e| e.0);
+            let cosmetics = self.matrix_cores.cosmetics.iter().filter(|e| e.1.state == state).map(|e| e.0);
 
-                for unforged_overclock in unforged_overclocks {
-                    let uuid_bytes = hex::decode(unforged_overclock.0).ok()?;
-                    buf.extend(uuid_bytes);
-                }
+            overclocks.chain(cosmetics)
+                .map(|uuid| Guid::from_hex(uuid).ok_or_else(|| SaveError::InvalidGuid(uuid.clone())))
+                .collect()
+        }
 
-                for unforged_cosmetic in unforged_cosmetics {
-                    let uuid_bytes = hex::decode(unforged_cosmetic.0).ok()?;
-                    buf.extend(uuid_bytes);
-                }
-            }
+        fn is_known_matrix_core(&self, guid: &Guid) -> bool {
+            let uuid = guid.to_hex();
+            self.matrix_cores.overclocks.contains_key(&uuid) || self.matrix_cores.cosmetics.contains_key(&uuid)
+        }
 
-            Some(())
+        // keep the order already in the save along with guids we don't know about,
+        // only newly added cores get appended
+        fn merge_guid_list(&self, existing: &[Guid], wanted: &HashSet<Guid>) -> Vec<Guid> {
+            let mut merged: Vec<Guid> = existing.iter().filter(|guid| wanted.contains(guid) || !self.is_known_matrix_core(guid)).copied().collect();
+            let mut added: Vec<Guid> = wanted.iter().filter(|guid| !existing.contains(guid)).copied().collect();
+            added.sort();
+            merged.extend(added);
+            merged
         }
 
-        fn save_matrix_cores(&mut self) -> Option<()> {
-            let start_pos = SaveFile::get_matrix_cores_start_pos(&self.buf)?;
-            let end_pos = SaveFile::get_matrix_cores_end_pos(&self.buf)?;
+        fn save_matrix_cores(&self, properties: &mut Vec<Property>) -> Result<(), SaveError> {
+            let forged = self.matrix_cores_in_state(OverclockState::Forged)?;
+            let unforged = self.matrix_cores_in_state(OverclockState::Unforged)?;
+            let schematics = gvas::find_property_list_mut(properties, &|list| list.iter().any(|p| p.name == FORGED_SCHEMATICS))
+                .ok_or_else(|| SaveError::missing(FORGED_SCHEMATICS))?;
 
-            let num_forged = u32::from_le_bytes(self.buf[start_pos+63..start_pos+67].try_into().ok()?);
-            let mut new_buf = Vec::new();
+            let forged_pos = schematics.iter().position(|p| p.name == FORGED_SCHEMATICS).ok_or_else(|| SaveError::missing(FORGED_SCHEMATICS))?;
+            let existing = SaveFile::get_guid_array(&schematics[forged_pos])?;
+            *schematics[forged_pos].value.as_struct_array_mut().ok_or_else(|| SaveError::unexpected_type(FORGED_SCHEMATICS, "an array of guids"))? =
+                self.merge_guid_list(&existing, &forged).into_iter().map(StructValue::Guid).collect();
 
-            new_buf.extend(&self.buf[..start_pos + (num_forged as usize * 16) + MATRIX_CORES_LIST_OFFSET]);
-            
-            self.save_unforged_matrix_cores(&mut new_buf)?;
+            let existing = match schematics.iter().find(|p| p.name == OWNED_SCHEMATICS) {
+                Some(e) => SaveFile::get_guid_array(e)?,
+                None => Vec::new()
+            };
+            let owned = self.merge_guid_list(&existing, &unforged);
+
+            match schematics.iter().position(|p| p.name == OWNED_SCHEMATICS) {
+                Some(pos) if owned.is_empty() => {
+                    schematics.remove(pos);
+                },
+                Some(pos) => {
+                    *schematics[pos].value.as_struct_array_mut().ok_or_else(|| SaveError::unexpected_type(OWNED_SCHEMATICS, "an array of guids"))? =
+                        owned.into_iter().map(StructValue::Guid).collect();
+                },
+                None if !owned.is_empty() => {
+                    schematics.insert(forged_pos + 1, Property::new(OWNED_SCHEMATICS, PropertyValue::guid_array(OWNED_SCHEMATICS, &owned)));
+                },
+                None => {}
+            }
 
-            new_buf.extend(&self.buf[end_pos..]);
-            
-            self.buf = new_buf;
-            Some(())
+            SaveFile::set_optional_int(schematics, FORGE_MASTER_XP, self.forge_xp())
         }
 
-        pub fn save(&mut self, mut file: &File) -> Result<(), Box<dyn Error>> {
-            self.set_val(self.eng_xp_pos, self.eng_xp);
-            self.set_val(self.gun_xp_pos, self.gun_xp);
-            self.set_val(self.scout_xp_pos, self.scout_xp);
-            self.set_val(self.drill_xp_pos, self.drill_xp);
+        fn save_properties(&self, properties: &mut Vec<Property>) -> Result<(), SaveError> {
+            for (class, progress) in &self.classes {
+                SaveFile::set_class_progress(properties, *class, progress)?;
+            }
+
+            SaveFile::set_optional_int(properties, PERK_POINTS, self.perkpoints)?;
+            SaveFile::set_int(properties, CREDITS, self.credits)?;
+            SaveFile::set_optional_int(properties, SCRIP, self.scrip)?;
+            seasons::save(&self.seasons, properties)?;
+            self.perks.save(properties)?;
+            self.weapons.save(properties)?;
+            self.vanity.save(properties)?;
+            milestones::save_milestones(&self.milestones, properties)?;
+            self.assignments.save(properties)?;
 
-            self.set_val(SaveFile::get_perkpoints_pos(&self.buf).ok_or("Failed to get perkpoints offset")?, self.perkpoints);
-            self.set_val(SaveFile::get_credits_pos(&self.buf).ok_or("Failed to get credits offset")?, self.credits);
+            self.save_resources(properties)?;
+            self.save_brewing(properties)?;
+            self.save_minerals(properties)?;
 
-            self.save_brewing();
-            self.save_minerals();
+            SaveFile::set_resource_val(properties, &ERROR_CORES, self.error_cores)?;
+            SaveFile::set_resource_val(properties, &BLANK_CORES, self.blank_cores)?;
 
-            let resources_pos = SaveFile::get_resources_pos(&self.buf).ok_or("Failed to get resources offset")?;
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &ERROR_CORES, self.error_cores);
-            SaveFile::set_resource_val(&mut self.buf, resources_pos, &BLANK_CORES, self.blank_cores);
+            self.save_matrix_cores(properties)
+        }
 
-            self.save_matrix_cores().ok_or("Failed to save overclocks!")?;
+        pub fn to_bytes(&self) -> Result<Vec<u8>, SaveError> {
+            let mut gvas = self.gvas.clone();
+            self.save_properties(&mut gvas.properties)?;
+            Ok(gvas.write())
+        }
 
-            file.write_all(&self.buf)?;
+        pub fn to_writer<W: Write>(&self, mut writer: W) -> Result<(), SaveError> {
+            writer.write_all(&self.to_bytes()?)?;
             Ok(())
         }
+
+        pub fn save(&self, file: &File) -> Result<(), SaveError> {
+            self.to_writer(file)
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use std::{env, fs, path::PathBuf};
+
+use deeprockgalactic_saveeditor::deep_rock_galactic::{BackupOptions, SaveFile, list_backups, restore_backup};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("drg-saveeditor-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn save_to_path_rotates_backups() {
+    let dir = temp_dir("rotate");
+    let path = dir.join("76561198000000000_Player.sav");
+    let original = common::save_fixture();
+    fs::write(&path, &original).unwrap();
+
+    let mut save_file = SaveFile::from_path(&path, common::MATRIX_CORES).unwrap();
+    let options = BackupOptions { keep: 2 };
+    for credits in 1..=3 {
+        save_file.credits = credits;
+        save_file.save_to_path(&path, &options).unwrap();
+    }
+
+    assert_eq!(SaveFile::from_path(&path, common::MATRIX_CORES).unwrap().credits, 3);
+
+    let backups = list_backups(&path).unwrap();
+    assert_eq!(backups.len(), 2);
+    assert!(backups[0].timestamp > backups[1].timestamp);
+    assert_eq!(SaveFile::from_path(&backups[0].path, common::MATRIX_CORES).unwrap().credits, 2);
+    assert_eq!(SaveFile::from_path(&backups[1].path, common::MATRIX_CORES).unwrap().credits, 1);
+
+    let leftovers: Vec<_> = fs::read_dir(&dir).unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn restore_backup_rolls_back() {
+    let dir = temp_dir("restore");
+    let path = dir.join("save.sav");
+    let original = common::save_fixture();
+    fs::write(&path, &original).unwrap();
+
+    let mut save_file = SaveFile::from_path(&path, common::MATRIX_CORES).unwrap();
+    save_file.credits = 0;
+    save_file.save_to_path(&path, &BackupOptions::default()).unwrap();
+    assert_ne!(fs::read(&path).unwrap(), original);
+
+    let backups = list_backups(&path).unwrap();
+    assert_eq!(backups.len(), 1);
+    restore_backup(&path, &backups[0]).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), original);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn backups_can_be_disabled() {
+    let dir = temp_dir("disabled");
+    let path = dir.join("save.sav");
+    fs::write(&path, common::save_fixture()).unwrap();
+
+    let save_file = SaveFile::from_path(&path, common::MATRIX_CORES).unwrap();
+    save_file.save_to_path(&path, &BackupOptions { keep: 0 }).unwrap();
+    assert!(list_backups(&path).unwrap().is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
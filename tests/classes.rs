@@ -0,0 +1,119 @@
+mod common;
+
+use deeprockgalactic_saveeditor::deep_rock_galactic::{DwarfClass, MAX_LEVEL, PROMOTION_PERK_POINTS, SaveError, SaveFile, promotion_title};
+
+#[test]
+fn class_progress_is_keyed_by_class() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert_eq!(save_file.classes[&DwarfClass::Driller].xp, 315_000);
+    assert_eq!(save_file.classes[&DwarfClass::Driller].promotions, 3);
+    assert_eq!(save_file.classes[&DwarfClass::Engineer].promotions, 1);
+
+    for class in DwarfClass::ALL {
+        save_file.classes.get_mut(&class).unwrap().xp += 1000;
+    }
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.classes[&DwarfClass::Engineer].xp, 26_000);
+    assert_eq!(reloaded.classes[&DwarfClass::Gunner].xp, 1000);
+}
+
+#[test]
+fn catalog_entries_name_their_class() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert_eq!(save_file.matrix_cores.overclocks[common::FORGED_OVERCLOCK].class, DwarfClass::Scout);
+}
+
+#[test]
+fn levels_follow_the_xp_table() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let engineer = &save_file.classes[&DwarfClass::Engineer];
+    assert_eq!((engineer.level(), engineer.xp_into_level()), (6, 0));
+    let scout = &save_file.classes[&DwarfClass::Scout];
+    assert_eq!((scout.level(), scout.xp_into_level()), (16, 1500));
+    assert_eq!(save_file.classes[&DwarfClass::Driller].level(), MAX_LEVEL);
+    assert_eq!(save_file.classes[&DwarfClass::Gunner].level(), 1);
+
+    save_file.set_level(DwarfClass::Scout, 25).unwrap();
+    assert!(matches!(save_file.set_level(DwarfClass::Gunner, 0), Err(SaveError::InvalidLevel(0))));
+    assert!(matches!(save_file.set_level(DwarfClass::Gunner, 26), Err(SaveError::InvalidLevel(26))));
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.classes[&DwarfClass::Scout].xp, 315_000);
+    assert_eq!(reloaded.classes[&DwarfClass::Scout].level(), 25);
+    assert_eq!(reloaded.classes[&DwarfClass::Gunner].xp, 0);
+}
+
+#[test]
+fn promotions_are_persisted() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert!(matches!(save_file.promote(DwarfClass::Scout), Err(SaveError::CannotPromote { level: 16, .. })));
+
+    save_file.promote(DwarfClass::Driller).unwrap();
+    save_file.classes.get_mut(&DwarfClass::Gunner).unwrap().promotions = 2;
+    assert_eq!(save_file.perkpoints, 17 + PROMOTION_PERK_POINTS);
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    let driller = &reloaded.classes[&DwarfClass::Driller];
+    assert_eq!((driller.xp, driller.promotions), (0, 4));
+    assert_eq!(reloaded.classes[&DwarfClass::Gunner].promotions, 2);
+    assert_eq!(reloaded.perkpoints, 17 + PROMOTION_PERK_POINTS);
+}
+
+#[test]
+fn player_rank_counts_every_class() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    // Engineer 1 promotion + level 6, Scout level 16, Driller 3 promotions + level 25, Gunner level 1
+    assert_eq!(save_file.player_rank(), 1 + (25 + 5) + 15 + (75 + 24));
+    assert_eq!(save_file.player_title().as_deref(), Some("Bronze III"));
+    assert_eq!(save_file.classes[&DwarfClass::Engineer].promotion_title().as_deref(), Some("Bronze I"));
+    assert_eq!(save_file.classes[&DwarfClass::Gunner].promotion_title(), None);
+    assert_eq!(promotion_title(4).as_deref(), Some("Silver I"));
+    assert_eq!(promotion_title(18).as_deref(), Some("Legendary III"));
+    assert_eq!(promotion_title(40).as_deref(), Some("Legendary III"));
+}
+
+#[test]
+fn negative_class_progress_is_refused() {
+    let mut properties = common::fixture_properties();
+    properties[0] = common::struct_array("CharacterSaves", "CharacterSave", &[
+        common::character(&common::ENGINEER, 25_000, -1),
+        common::character(&common::SCOUT, 150_000, 0),
+        common::character(&common::DRILLER, 315_000, 3),
+        common::character(&common::GUNNER, 0, 0)
+    ]);
+    let buf = common::build_save(&properties);
+
+    match SaveFile::new(&buf, common::MATRIX_CORES) {
+        Err(SaveError::UnexpectedType { section, .. }) => assert_eq!(section, "Engineer NumPromotions"),
+        other => panic!("unexpected result {:?}", other.map(|_| ()))
+    }
+}
+
+#[test]
+fn huge_class_progress_saturates() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    save_file.classes.get_mut(&DwarfClass::Gunner).unwrap().promotions = u32::MAX;
+    save_file.classes.get_mut(&DwarfClass::Scout).unwrap().xp = u32::MAX;
+    assert_eq!(save_file.player_rank(), u32::MAX);
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.classes[&DwarfClass::Gunner].promotions, i32::MAX as u32);
+    assert_eq!(reloaded.classes[&DwarfClass::Scout].xp, i32::MAX as u32);
+}
+
+#[test]
+fn promoting_past_the_perk_point_range_is_refused() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    save_file.perkpoints = u32::MAX;
+    let before = save_file.clone();
+
+    assert!(matches!(save_file.promote(DwarfClass::Driller), Err(SaveError::Overflow(_))));
+    assert_eq!(save_file, before);
+}
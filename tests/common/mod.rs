@@ -0,0 +1,340 @@
+#![allow(dead_code)]
+
+// Hand-rolled GVAS encoder so the fixtures don't depend on the writer under test
+
+pub const ENGINEER: [u8; 16] = [0x85, 0xEF, 0x62, 0x6C, 0x65, 0xF1, 0x02, 0x4A, 0x8D, 0xFE, 0xB5, 0xD0, 0xF3, 0x90, 0x9D, 0x2E];
+pub const SCOUT: [u8; 16] = [0x30, 0xD8, 0xEA, 0x17, 0xD8, 0xFB, 0xBA, 0x4C, 0x95, 0x30, 0x6D, 0xE9, 0x65, 0x5C, 0x2F, 0x8C];
+pub const DRILLER: [u8; 16] = [0x9E, 0xDD, 0x56, 0xF1, 0xEE, 0xBC, 0xC5, 0x48, 0x8D, 0x5B, 0x5E, 0x5B, 0x80, 0xB6, 0x2D, 0xB4];
+pub const GUNNER: [u8; 16] = [0xAE, 0x56, 0xE1, 0x80, 0xFE, 0xC0, 0xC4, 0x4D, 0x96, 0xFA, 0x29, 0xC2, 0x83, 0x66, 0xB9, 0x7B];
+
+pub const BISMOR: [u8; 16] = [0xAF, 0x0D, 0xC4, 0xFE, 0x83, 0x61, 0xBB, 0x48, 0xB3, 0x2C, 0x92, 0xCC, 0x97, 0xE2, 0x1D, 0xE7];
+pub const ENOR: [u8; 16] = [0x48, 0x8D, 0x05, 0x14, 0x6F, 0x5F, 0x75, 0x4B, 0xA3, 0xD4, 0x61, 0x0D, 0x08, 0xC0, 0x60, 0x3E];
+pub const JADIZ: [u8; 16] = [0x22, 0xBC, 0x4F, 0x7D, 0x07, 0xD1, 0x3E, 0x43, 0xBF, 0xCA, 0x81, 0xBD, 0x9C, 0x14, 0xB1, 0xAF];
+pub const CROPPA: [u8; 16] = [0x8A, 0xA7, 0xFB, 0x43, 0x29, 0x3A, 0x0B, 0x49, 0xB8, 0xBE, 0x42, 0xFF, 0xE0, 0x68, 0xA4, 0x4C];
+pub const MAGNITE: [u8; 16] = [0xAA, 0xDE, 0xD8, 0x76, 0x6C, 0x22, 0x7D, 0x40, 0x80, 0x32, 0xAF, 0xD1, 0x8D, 0x63, 0x56, 0x1E];
+pub const UMANITE: [u8; 16] = [0x5F, 0x2B, 0xCF, 0x83, 0x47, 0x76, 0x0A, 0x42, 0xA2, 0x3B, 0x6E, 0xDC, 0x07, 0xC0, 0x94, 0x1D];
+pub const YEAST: [u8; 16] = [0x07, 0x85, 0x48, 0xB9, 0x32, 0x32, 0xC0, 0x40, 0x85, 0xF8, 0x92, 0xE0, 0x84, 0xA7, 0x41, 0x00];
+pub const STARCH: [u8; 16] = [0x72, 0x31, 0x22, 0x04, 0xE2, 0x87, 0xBC, 0x41, 0x81, 0x55, 0x40, 0xA0, 0xCF, 0x88, 0x12, 0x80];
+pub const BARLEY: [u8; 16] = [0x22, 0xDA, 0xA7, 0x57, 0xAD, 0x7A, 0x80, 0x49, 0x89, 0x1B, 0x17, 0xED, 0xCC, 0x2F, 0xE0, 0x98];
+pub const MALT: [u8; 16] = [0x41, 0xEA, 0x55, 0x0C, 0x1D, 0x46, 0xC5, 0x4B, 0xBE, 0x2E, 0x9C, 0xA5, 0xA7, 0xAC, 0xCB, 0x06];
+pub const ERROR_CORES: [u8; 16] = [0x58, 0x28, 0x65, 0x2C, 0x9A, 0x5D, 0xE8, 0x45, 0xA9, 0xE2, 0xE1, 0xB8, 0xB4, 0x63, 0xC5, 0x16];
+pub const BLANK_CORES: [u8; 16] = [0xA1, 0x0C, 0xB2, 0x85, 0x38, 0x71, 0xFB, 0x49, 0x9A, 0xC8, 0x54, 0xA1, 0xCD, 0xE2, 0x20, 0x2C];
+// Stand-in for a resource the editor has no name for
+pub const UNNAMED_RESOURCE: [u8; 16] = [0x0F, 0x1E, 0x2D, 0x3C, 0x4B, 0x5A, 0x69, 0x78, 0x87, 0x96, 0xA5, 0xB4, 0xC3, 0xD2, 0xE1, 0xF0];
+pub const UNNAMED_RESOURCE_HEX: &str = "0F1E2D3C4B5A69788796A5B4C3D2E1F0";
+
+// Scout "Electrifying Reload" and "AI Stability Engine", Engineer "Beyond The Circuit" in matrix_cores.json
+pub const FORGED_OVERCLOCK: &str = "FF94B9E7834D774292DACCCA3EA023B9";
+pub const UNFORGED_OVERCLOCK: &str = "11F123E174103140957957506BD576D7";
+pub const FORGED_COSMETIC: &str = "6D10449F24DD664B9A74BBC5F27ACB45";
+
+// Made up perks, the crate doesn't ship a perk catalog
+pub const PASSIVE_PERK: &str = "0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A";
+pub const ACTIVE_PERK: &str = "0B0B0B0B0B0B0B0B0B0B0B0B0B0B0B0B";
+pub const LOCKED_PERK: &str = "0C0C0C0C0C0C0C0C0C0C0C0C0C0C0C0C";
+pub const PERKS: &str = r#"{
+  "perks": {
+    "0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A": { "name": "Resupplier", "kind": "Passive", "tier_costs": [1, 2, 3] },
+    "0B0B0B0B0B0B0B0B0B0B0B0B0B0B0B0B": { "name": "Dash", "kind": "Active", "tier_costs": [2, 4] },
+    "0C0C0C0C0C0C0C0C0C0C0C0C0C0C0C0C": { "name": "Iron Will", "kind": "Active", "tier_costs": [5, 5, 5] }
+  }
+}"#;
+
+// Made up weapon and upgrade guids, the Deepcore name matches the overclock catalog
+pub const DEEPCORE: &str = "1D1D1D1D1D1D1D1D1D1D1D1D1D1D1D1D";
+pub const GK2_DAMAGE: &str = "2A2A2A2A2A2A2A2A2A2A2A2A2A2A2A2A";
+pub const GK2_AMMO: &str = "2B2B2B2B2B2B2B2B2B2B2B2B2B2B2B2B";
+pub const GK2_RELOAD: &str = "2C2C2C2C2C2C2C2C2C2C2C2C2C2C2C2C";
+pub const WEAPONS: &str = r#"{
+  "weapons": {
+    "1D1D1D1D1D1D1D1D1D1D1D1D1D1D1D1D": { "class": "Scout", "name": "Deepcore GK2" }
+  },
+  "upgrades": {
+    "2A2A2A2A2A2A2A2A2A2A2A2A2A2A2A2A": { "weapon": "Deepcore GK2", "name": "Gyro Stabilisation", "tier": 1,
+      "cost": { "credits": 1000, "bismor": 0, "croppa": 0, "enor": 0, "jadiz": 0, "magnite": 20, "umanite": 0 } },
+    "2B2B2B2B2B2B2B2B2B2B2B2B2B2B2B2B": { "weapon": "Deepcore GK2", "name": "Expanded Ammo Bags", "tier": 1,
+      "cost": { "credits": 1000, "bismor": 0, "croppa": 20, "enor": 0, "jadiz": 0, "magnite": 0, "umanite": 0 } },
+    "2C2C2C2C2C2C2C2C2C2C2C2C2C2C2C2C": { "weapon": "Deepcore GK2", "name": "Quickfire Ejector", "tier": 2,
+      "cost": { "credits": 1800, "bismor": 0, "croppa": 0, "enor": 18, "jadiz": 0, "magnite": 0, "umanite": 12 } }
+  }
+}"#;
+
+// Made up vanity items
+pub const PAINTJOB: &str = "3A3A3A3A3A3A3A3A3A3A3A3A3A3A3A3A";
+pub const BEARD: &str = "3B3B3B3B3B3B3B3B3B3B3B3B3B3B3B3B";
+pub const VICTORY_POSE: &str = "3C3C3C3C3C3C3C3C3C3C3C3C3C3C3C3C";
+pub const VANITY: &str = r#"{
+  "items": {
+    "3A3A3A3A3A3A3A3A3A3A3A3A3A3A3A3A": { "kind": "ArmorPaintjob", "name": "Pitch Black", "class": "Driller" },
+    "3B3B3B3B3B3B3B3B3B3B3B3B3B3B3B3B": { "kind": "Beard", "name": "The Stonebreaker" },
+    "3C3C3C3C3C3C3C3C3C3C3C3C3C3C3C3C": { "kind": "VictoryPose", "name": "Rock and Stone" }
+  }
+}"#;
+
+// Made up assignments
+pub const ACTIVE_ASSIGNMENT: &str = "5A5A5A5A5A5A5A5A5A5A5A5A5A5A5A5A";
+pub const COMPLETED_ASSIGNMENT: &str = "5B5B5B5B5B5B5B5B5B5B5B5B5B5B5B5B";
+
+pub const MATRIX_CORES: &str = include_str!("../../matrix_cores.json");
+
+pub fn guid(hex: &str) -> [u8; 16] {
+    let mut guid = [0; 16];
+    for (i, byte) in guid.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+    }
+    guid
+}
+
+pub fn string(s: &str) -> Vec<u8> {
+    let mut buf = Vec::new();
+    if s.is_ascii() {
+        buf.extend((s.len() as i32 + 1).to_le_bytes());
+        buf.extend(s.as_bytes());
+        buf.push(0);
+    } else {
+        let units: Vec<u16> = s.encode_utf16().collect();
+        buf.extend((-(units.len() as i32 + 1)).to_le_bytes());
+        for unit in units.iter().chain(&[0]) {
+            buf.extend(unit.to_le_bytes());
+        }
+    }
+    buf
+}
+
+pub fn none() -> Vec<u8> {
+    string("None")
+}
+
+// `header` is everything between the size and the value, including the property guid flag
+pub fn property(name: &str, type_name: &str, header: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut buf = string(name);
+    buf.extend(string(type_name));
+    buf.extend((value.len() as u32).to_le_bytes());
+    buf.extend(0u32.to_le_bytes());
+    buf.extend(header);
+    buf.extend(value);
+    buf
+}
+
+pub fn int(name: &str, value: i32) -> Vec<u8> {
+    property(name, "IntProperty", &[0], &value.to_le_bytes())
+}
+
+pub fn float(name: &str, value: f32) -> Vec<u8> {
+    property(name, "FloatProperty", &[0], &value.to_le_bytes())
+}
+
+pub fn boolean(name: &str, value: bool) -> Vec<u8> {
+    property(name, "BoolProperty", &[value as u8, 0], &[])
+}
+
+pub fn str(name: &str, value: &str) -> Vec<u8> {
+    property(name, "StrProperty", &[0], &string(value))
+}
+
+pub fn struct_header(struct_type: &str) -> Vec<u8> {
+    let mut header = string(struct_type);
+    header.extend([0; 16]);
+    header.push(0);
+    header
+}
+
+pub fn struct_property(name: &str, struct_type: &str, properties: &[Vec<u8>]) -> Vec<u8> {
+    let mut value = properties.concat();
+    value.extend(none());
+    property(name, "StructProperty", &struct_header(struct_type), &value)
+}
+
+pub fn guid_property(name: &str, guid: &[u8; 16]) -> Vec<u8> {
+    property(name, "StructProperty", &struct_header("Guid"), guid)
+}
+
+pub fn array_header(inner_type: &str) -> Vec<u8> {
+    let mut header = string(inner_type);
+    header.push(0);
+    header
+}
+
+pub fn struct_array(name: &str, struct_type: &str, elements: &[Vec<u8>]) -> Vec<u8> {
+    let data = elements.concat();
+    let mut value = (elements.len() as u32).to_le_bytes().to_vec();
+    value.extend(property(name, "StructProperty", &struct_header(struct_type), &data));
+    property(name, "ArrayProperty", &array_header("StructProperty"), &value)
+}
+
+pub fn int_array(name: &str, values: &[i32]) -> Vec<u8> {
+    let mut value = (values.len() as u32).to_le_bytes().to_vec();
+    for v in values {
+        value.extend(v.to_le_bytes());
+    }
+    property(name, "ArrayProperty", &array_header("IntProperty"), &value)
+}
+
+pub fn guid_array(name: &str, guids: &[[u8; 16]]) -> Vec<u8> {
+    let elements: Vec<Vec<u8>> = guids.iter().map(|g| g.to_vec()).collect();
+    struct_array(name, "Guid", &elements)
+}
+
+pub fn struct_body(properties: &[Vec<u8>]) -> Vec<u8> {
+    let mut body = properties.concat();
+    body.extend(none());
+    body
+}
+
+pub fn map(name: &str, key_type: &str, value_type: &str, entries: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    let mut header = string(key_type);
+    header.extend(string(value_type));
+    header.push(0);
+
+    let mut value = 0u32.to_le_bytes().to_vec();
+    value.extend((entries.len() as u32).to_le_bytes());
+    for (k, v) in entries {
+        value.extend(k);
+        value.extend(v);
+    }
+    property(name, "MapProperty", &header, &value)
+}
+
+pub fn guid_float_map(name: &str, entries: &[([u8; 16], f32)]) -> Vec<u8> {
+    let entries: Vec<(Vec<u8>, Vec<u8>)> = entries.iter().map(|(k, v)| (k.to_vec(), v.to_le_bytes().to_vec())).collect();
+    map(name, "StructProperty", "FloatProperty", &entries)
+}
+
+pub fn header() -> Vec<u8> {
+    let mut buf = b"GVAS".to_vec();
+    buf.extend(2u32.to_le_bytes());
+    buf.extend(522u32.to_le_bytes());
+    buf.extend(4u16.to_le_bytes());
+    buf.extend(27u16.to_le_bytes());
+    buf.extend(2u16.to_le_bytes());
+    buf.extend(0u32.to_le_bytes());
+    buf.extend(string("++UE4+Release-4.27"));
+    buf.extend(3u32.to_le_bytes());
+    buf.extend(2u32.to_le_bytes());
+    buf.extend([0x22; 16]);
+    buf.extend(1u32.to_le_bytes());
+    buf.extend([0x11; 16]);
+    buf.extend(7u32.to_le_bytes());
+    buf.extend(string("/Script/FSD.FSDSaveGame"));
+    buf
+}
+
+pub fn character(class_id: &[u8; 16], xp: i32, promotions: i32) -> Vec<u8> {
+    struct_body(&[
+        guid_property("SavegameID", class_id),
+        int("XP", xp),
+        int("NumPromotions", promotions)
+    ])
+}
+
+pub fn character_with_perks(class_id: &[u8; 16], xp: i32, promotions: i32, active: &[[u8; 16]], passive: &[[u8; 16]]) -> Vec<u8> {
+    struct_body(&[
+        guid_property("SavegameID", class_id),
+        int("XP", xp),
+        int("NumPromotions", promotions),
+        int("MissionsCompleted", 212),
+        guid_array("EquippedActivePerks", active),
+        guid_array("EquippedPassivePerks", passive)
+    ])
+}
+
+pub fn perk_unlock(perk: &str, tier: i32) -> Vec<u8> {
+    struct_body(&[guid_property("PerkID", &guid(perk)), int("Tier", tier)])
+}
+
+pub fn resources() -> Vec<([u8; 16], f32)> {
+    vec![
+        (BISMOR, 120.0), (ENOR, 80.0), (JADIZ, 64.5), (CROPPA, 200.0), (MAGNITE, 33.0), (UMANITE, 12.0),
+        (YEAST, 5.0), (STARCH, 6.0), (BARLEY, 7.0), (MALT, 8.0),
+        (ERROR_CORES, 3.0), (BLANK_CORES, 4.0),
+        (UNNAMED_RESOURCE, 42.0)
+    ]
+}
+
+// Assorted property kinds the editor doesn't model, they must survive a round trip untouched
+pub fn unmodelled_properties() -> Vec<Vec<u8>> {
+    let date_time = struct_header("DateTime");
+    vec![
+        str("LastSaveVersion", "1.38.71585.0"),
+        str("PlayerName", "Kärl Ünderhill ⛏"),
+        property("LastPlayed", "StructProperty", &date_time, &637_000_000_000_000_000i64.to_le_bytes()),
+        property("Difficulty", "ByteProperty", &[string("None"), vec![0]].concat(), &[3]),
+        property("Biome", "EnumProperty", &[string("EBiome"), vec![0]].concat(), &string("EBiome::MagmaCore")),
+        property("MissionSeed", "Int64Property", &[0], &(-42i64).to_le_bytes()),
+        property("FlavorText", "TextProperty", &[0], &[0x02, 0x00, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x00, 0x00]),
+        property("SeenMissions", "SetProperty", &array_header("IntProperty"), &[0, 0, 0, 0, 2, 0, 0, 0, 7, 0, 0, 0, 9, 0, 0, 0]),
+        property("DrinkCounts", "ArrayProperty", &array_header("IntProperty"), &[2, 0, 0, 0, 1, 0, 0, 0, 5, 0, 0, 0]),
+        boolean("bHasOpenedDeepDiveTerminal", true)
+    ]
+}
+
+pub fn fixture_properties() -> Vec<Vec<u8>> {
+    fixture_properties_with_resources(&resources())
+}
+
+pub fn fixture_properties_with_resources(resources: &[([u8; 16], f32)]) -> Vec<Vec<u8>> {
+    let mut properties = vec![
+        struct_array("CharacterSaves", "CharacterSave", &[
+            character_with_perks(&ENGINEER, 25_000, 1, &[guid(ACTIVE_PERK)], &[guid(PASSIVE_PERK)]),
+            character(&SCOUT, 150_000, 0),
+            character(&DRILLER, 315_000, 3),
+            character(&GUNNER, 0, 0)
+        ]),
+        int("Credits", 123_456),
+        int("PerkPoints", 17),
+        int("Scrip", 9),
+        map("MilestoneCounters", "NameProperty", "IntProperty", &[
+            (string("EnemiesKilled"), 48_211i32.to_le_bytes().to_vec()),
+            (string("MineralsMined"), 90_000i32.to_le_bytes().to_vec())
+        ]),
+        guid_property("ActiveAssignment", &guid(ACTIVE_ASSIGNMENT)),
+        struct_array("Assignments", "AssignmentSave", &[
+            struct_body(&[guid_property("AssignmentID", &guid(COMPLETED_ASSIGNMENT)), int("Progress", 5), boolean("bCompleted", true)]),
+            struct_body(&[guid_property("AssignmentID", &guid(ACTIVE_ASSIGNMENT)), int("Progress", 2), boolean("bCompleted", false)])
+        ]),
+        int("DeepDivesCompleted", 14),
+        int("EliteDeepDivesCompleted", 3),
+        boolean("bWeeklyDeepDiveCompleted", true),
+        struct_array("Seasons", "SeasonSave", &[struct_body(&[
+            int("SeasonID", 4),
+            int("SeasonXP", 62_500),
+            int_array("ClaimedNodes", &[0, 3, 7])
+        ])]),
+        guid_array("UnlockedArmorPaintjobs", &[guid(PAINTJOB)]),
+        guid_array("UnlockedBeards", &[guid(BEARD)]),
+        guid_array("OwnedUpgrades", &[guid(GK2_DAMAGE), guid(GK2_AMMO)]),
+        map("WeaponMaintenance", "StructProperty", "StructProperty", &[
+            (guid(DEEPCORE).to_vec(), struct_body(&[int("Level", 2), int("XP", 1500)]))
+        ]),
+        struct_array("WeaponLoadouts", "WeaponLoadout", &[struct_body(&[
+            guid_property("WeaponID", &guid(DEEPCORE)),
+            int("LoadoutIndex", 0),
+            guid_array("SelectedUpgrades", &[guid(GK2_DAMAGE)]),
+            boolean("bFavorite", true)
+        ])]),
+        struct_array("UnlockedPerks", "PerkUnlock", &[perk_unlock(PASSIVE_PERK, 3), perk_unlock(ACTIVE_PERK, 1)]),
+        struct_property("Resources", "ResourcesSave", &[
+            guid_float_map("OwnedResources", resources)
+        ]),
+        struct_property("SchematicSave", "SchematicSave", &[
+            guid_array("ForgedSchematics", &[guid(FORGED_OVERCLOCK), guid(FORGED_COSMETIC)]),
+            guid_array("OwnedSchematics", &[guid(UNFORGED_OVERCLOCK)]),
+            boolean("bFirstSchematicMessageShown", true),
+            int("ForgeMasterXP", 12_500)
+        ])
+    ];
+    properties.extend(unmodelled_properties());
+    properties
+}
+
+pub fn build_save(properties: &[Vec<u8>]) -> Vec<u8> {
+    let mut buf = header();
+    buf.extend(properties.concat());
+    buf.extend(none());
+    buf.extend(0u32.to_le_bytes());
+    buf
+}
+
+pub fn save_fixture() -> Vec<u8> {
+    build_save(&fixture_properties())
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use deeprockgalactic_saveeditor::deep_rock_galactic::{SaveError, SaveFile};
+
+#[test]
+fn missing_section_is_named() {
+    let properties: Vec<Vec<u8>> = common::fixture_properties().into_iter()
+        .filter(|p| !p.windows(7).any(|w| w == b"Credits"))
+        .collect();
+    let buf = common::build_save(&properties);
+
+    match SaveFile::new(&buf, common::MATRIX_CORES) {
+        Err(SaveError::MissingSection { section }) => assert_eq!(section, "Credits"),
+        other => panic!("unexpected result {:?}", other.map(|_| ()))
+    }
+}
+
+#[test]
+fn negative_counts_are_refused() {
+    let properties: Vec<Vec<u8>> = common::fixture_properties().into_iter()
+        .map(|p| if p.windows(7).any(|w| w == b"Credits") { common::int("Credits", -5) } else { p })
+        .collect();
+    let buf = common::build_save(&properties);
+
+    match SaveFile::new(&buf, common::MATRIX_CORES) {
+        Err(SaveError::UnexpectedType { section, .. }) => assert_eq!(section, "Credits"),
+        other => panic!("unexpected result {:?}", other.map(|_| ()))
+    }
+}
+
+#[test]
+fn counts_past_the_save_range_are_capped() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    save_file.credits = u32::MAX;
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.credits, i32::MAX as u32);
+}
+
+#[test]
+fn truncated_save_reports_offset() {
+    let mut buf = common::save_fixture();
+    buf.truncate(100);
+
+    match SaveFile::new(&buf, common::MATRIX_CORES) {
+        Err(SaveError::UnexpectedEof { offset, .. }) => assert!(offset <= 100),
+        other => panic!("unexpected result {:?}", other.map(|_| ()))
+    }
+}
+
+#[test]
+fn bad_magic_is_invalid_data() {
+    let mut buf = common::save_fixture();
+    buf[0] = b'X';
+
+    match SaveFile::new(&buf, common::MATRIX_CORES) {
+        Err(SaveError::InvalidData { offset: 0, expected }) => assert_eq!(expected, "GVAS magic"),
+        other => panic!("unexpected result {:?}", other.map(|_| ()))
+    }
+}
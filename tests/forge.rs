@@ -0,0 +1,218 @@
+mod common;
+
+use deeprockgalactic_saveeditor::{deep_rock_galactic::{Cost, FORGE_XP_PER_ITEM, FORGE_XP_PER_LEVEL, ForgePriorities, Minerals, OverclockState, SaveError, SaveFile}, gvas::{self, Gvas}};
+
+#[test]
+fn forge_xp_follows_forged_items() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert_eq!(save_file.forge_xp(), 12_500);
+    assert_eq!(save_file.forge_level(), 3);
+
+    save_file.matrix_cores.overclocks.get_mut(common::UNFORGED_OVERCLOCK).unwrap().state = OverclockState::Forged;
+    save_file.matrix_cores.overclocks.get_mut("C3060324CE482C4AB6DC44ED498CBA39").unwrap().state = OverclockState::Forged;
+    assert_eq!(save_file.forge_xp(), 12_500 + 2 * FORGE_XP_PER_ITEM);
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.forge_xp(), 12_500 + 2 * FORGE_XP_PER_ITEM);
+}
+
+#[test]
+fn setting_forge_xp_starts_over_from_the_current_items() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    save_file.matrix_cores.overclocks.get_mut(common::UNFORGED_OVERCLOCK).unwrap().state = OverclockState::Forged;
+    save_file.set_forge_level(10).unwrap();
+    assert_eq!(save_file.forge_level(), 10);
+    assert!(save_file.set_forge_level(0).is_err());
+    assert!(matches!(save_file.set_forge_level(u32::MAX / FORGE_XP_PER_LEVEL + 2), Err(SaveError::InvalidLevel(_))));
+    assert_eq!(save_file.forge_level(), 10);
+
+    save_file.matrix_cores.overclocks.get_mut(common::FORGED_OVERCLOCK).unwrap().state = OverclockState::Unacquired;
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.forge_xp(), 45_000 - FORGE_XP_PER_ITEM);
+}
+
+#[test]
+fn forging_spends_credits_and_minerals() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let cost = save_file.matrix_cores.overclocks[common::UNFORGED_OVERCLOCK].cost.clone();
+    save_file.minerals.enor = cost.enor as f32 + 5.0;
+    save_file.minerals.umanite = cost.umanite as f32;
+
+    save_file.forge(common::UNFORGED_OVERCLOCK).unwrap();
+    assert_eq!(save_file.credits, 123_456 - cost.credits);
+    assert_eq!(save_file.minerals.enor, 5.0);
+    assert_eq!(save_file.matrix_cores.overclocks[common::UNFORGED_OVERCLOCK].state, OverclockState::Forged);
+    assert!(matches!(save_file.forge(common::UNFORGED_OVERCLOCK), Err(SaveError::NotForgeable(_))));
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.matrix_cores.overclocks[common::UNFORGED_OVERCLOCK].state, OverclockState::Forged);
+    assert_eq!(reloaded.credits, 123_456 - cost.credits);
+    assert_eq!(reloaded.forge_xp(), 12_500 + FORGE_XP_PER_ITEM);
+}
+
+#[test]
+fn forging_without_funds_reports_whats_missing() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let cost = save_file.matrix_cores.overclocks[common::UNFORGED_OVERCLOCK].cost.clone();
+    save_file.credits = 100;
+    save_file.minerals.enor = 0.0;
+
+    match save_file.forge(common::UNFORGED_OVERCLOCK) {
+        Err(SaveError::InsufficientFunds { missing, .. }) => {
+            assert_eq!(missing, Cost { credits: cost.credits - 100, enor: cost.enor, umanite: cost.umanite - 12, ..Cost::default() });
+        },
+        other => panic!("expected insufficient funds, got {:?}", other)
+    }
+    assert_eq!(save_file.credits, 100);
+    assert_eq!(save_file.matrix_cores.overclocks[common::UNFORGED_OVERCLOCK].state, OverclockState::Unforged);
+}
+
+#[test]
+fn forge_all_affordable_stops_when_funds_run_out() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    for uuid in &["C3060324CE482C4AB6DC44ED498CBA39", "7306221F1264B04B8C025DB4DE9D1667"] {
+        save_file.matrix_cores.overclocks.get_mut(*uuid).unwrap().state = OverclockState::Unforged;
+    }
+    save_file.credits = 1_000_000;
+    save_file.minerals = Minerals { bismor: 10_000.0, enor: 10_000.0, jadiz: 10_000.0, croppa: 10_000.0, magnite: 10_000.0, umanite: 10_000.0 };
+
+    let mut forged = save_file.forge_all_affordable().unwrap();
+    forged.sort();
+    let mut expected = vec![common::UNFORGED_OVERCLOCK, "C3060324CE482C4AB6DC44ED498CBA39", "7306221F1264B04B8C025DB4DE9D1667"];
+    expected.sort();
+    assert_eq!(forged, expected);
+    assert!(save_file.forge_all_affordable().unwrap().is_empty());
+}
+
+#[test]
+fn forge_plan_counts_every_credit() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    save_file.matrix_cores.overclocks.get_mut(common::UNFORGED_OVERCLOCK).unwrap().cost = Cost { credits: 16_777_220, ..Cost::default() };
+    save_file.credits = 16_777_219;
+
+    let plan = save_file.plan_forging(&ForgePriorities::default());
+    assert!(!plan.items.iter().any(|uuid| uuid == common::UNFORGED_OVERCLOCK));
+    assert_eq!(plan.leftover_credits, save_file.credits - plan.cost.credits);
+    assert!(!save_file.forge_all_affordable().unwrap().iter().any(|uuid| uuid == common::UNFORGED_OVERCLOCK));
+}
+
+#[test]
+fn forge_all_affordable_reports_bad_catalog_entries() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let mut broken = save_file.matrix_cores.overclocks[common::UNFORGED_OVERCLOCK].clone();
+    broken.cost = Cost::default();
+    save_file.matrix_cores.overclocks.insert("not a guid".to_string(), broken);
+    let before = save_file.clone();
+
+    assert!(matches!(save_file.forge_all_affordable(), Err(SaveError::InvalidGuid(_))));
+    assert_eq!(save_file, before);
+}
+
+fn limited_forge(save_file: &mut SaveFile) -> Vec<String> {
+    let mut uuids: Vec<String> = save_file.matrix_cores.overclocks.keys().cloned().collect();
+    uuids.sort();
+    for (i, uuid) in uuids.iter().enumerate() {
+        save_file.matrix_cores.overclocks.get_mut(uuid).unwrap().state = if i < 12 { OverclockState::Unforged } else { OverclockState::Forged };
+    }
+    for cosmetic in save_file.matrix_cores.cosmetics.values_mut() {
+        cosmetic.state = OverclockState::Forged;
+    }
+    save_file.credits = 30_000;
+    save_file.minerals = Minerals { bismor: 400.0, enor: 400.0, jadiz: 400.0, croppa: 400.0, magnite: 400.0, umanite: 400.0 };
+    uuids.truncate(12);
+    uuids
+}
+
+fn affordable(save_file: &SaveFile, uuids: &[&String]) -> bool {
+    let mut total = Cost::default();
+    for uuid in uuids {
+        let cost = &save_file.matrix_cores.overclocks[uuid.as_str()].cost;
+        total.credits += cost.credits;
+        total.bismor += cost.bismor;
+        total.croppa += cost.croppa;
+        total.enor += cost.enor;
+        total.jadiz += cost.jadiz;
+        total.magnite += cost.magnite;
+        total.umanite += cost.umanite;
+    }
+    total.shortfall(save_file.credits, &save_file.minerals).is_none()
+}
+
+#[test]
+fn forge_plan_forges_as_many_items_as_possible() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let uuids = limited_forge(&mut save_file);
+
+    let best = (0u32..1 << uuids.len())
+        .map(|mask| uuids.iter().enumerate().filter(|(i, _)| mask & (1 << i) != 0).map(|(_, uuid)| uuid).collect::<Vec<_>>())
+        .filter(|subset| affordable(&save_file, subset))
+        .map(|subset| subset.len())
+        .max()
+        .unwrap();
+    assert!(best > 0 && best < uuids.len());
+
+    let plan = save_file.plan_forging(&ForgePriorities::default());
+    assert!(plan.optimal);
+    assert_eq!(plan.items.len(), best);
+    assert_eq!(plan.score as usize, best);
+    assert!(affordable(&save_file, &plan.items.iter().collect::<Vec<_>>()));
+    assert_eq!(plan.leftover_credits, save_file.credits - plan.cost.credits);
+    assert_eq!(plan.leftover_minerals.enor, 400.0 - plan.cost.enor as f32);
+    assert_eq!(save_file.clone().forge_all_affordable().unwrap(), plan.items);
+
+    for uuid in &plan.items {
+        save_file.forge(uuid).unwrap();
+    }
+    assert_eq!(save_file.credits, plan.leftover_credits);
+    assert_eq!(save_file.minerals, plan.leftover_minerals);
+}
+
+#[test]
+fn forge_plan_follows_priorities() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let uuids = limited_forge(&mut save_file);
+    let favourite = &save_file.matrix_cores.overclocks[uuids[0].as_str()];
+    let (class, weapon) = (favourite.class, favourite.weapon.clone());
+
+    let mut priorities = ForgePriorities::default();
+    priorities.classes.insert(class, 0);
+    let plan = save_file.plan_forging(&priorities);
+    assert!(plan.items.iter().all(|uuid| save_file.matrix_cores.overclocks[uuid.as_str()].class != class));
+
+    priorities.weapons.insert(weapon.clone(), 100);
+    let plan = save_file.plan_forging(&priorities);
+    let wanted: Vec<&String> = uuids.iter().filter(|uuid| save_file.matrix_cores.overclocks[uuid.as_str()].weapon == weapon).collect();
+    if affordable(&save_file, &wanted) {
+        assert!(wanted.iter().all(|uuid| plan.items.contains(uuid)));
+    }
+    assert!(plan.items.iter().all(|uuid| {
+        let oc = &save_file.matrix_cores.overclocks[uuid.as_str()];
+        oc.class != class || oc.weapon == weapon
+    }));
+}
+
+#[test]
+fn missing_forge_xp_is_added_next_to_the_schematics() {
+    let mut properties = common::fixture_properties();
+    let schematics = properties.iter().position(|p| p.windows(13).any(|w| w == b"SchematicSave")).unwrap();
+    properties[schematics] = common::struct_property("SchematicSave", "SchematicSave", &[
+        common::guid_array("ForgedSchematics", &[common::guid(common::FORGED_OVERCLOCK)])
+    ]);
+    let mut save_file = SaveFile::new(&common::build_save(&properties), common::MATRIX_CORES).unwrap();
+    assert_eq!(save_file.forge_xp(), 0);
+    save_file.set_forge_level(3).unwrap();
+
+    let gvas = Gvas::read(&save_file.to_bytes().unwrap()).unwrap();
+    assert!(gvas.properties.iter().all(|p| p.name != "ForgeMasterXP"));
+    let schematics = gvas::find_property_list(&gvas.properties, &|list| list.iter().any(|p| p.name == "ForgedSchematics")).unwrap();
+    assert!(schematics.iter().any(|p| p.name == "ForgeMasterXP"));
+}
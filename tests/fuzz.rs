@@ -0,0 +1,75 @@
+mod common;
+
+use deeprockgalactic_saveeditor::{deep_rock_galactic::SaveFile, gvas::Gvas};
+
+// xorshift64*, deterministic so failures can be reproduced
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+fn load(buf: &[u8]) {
+    let _ = SaveFile::new(buf, common::MATRIX_CORES).map(|save_file| save_file.to_bytes());
+    let _ = Gvas::read(buf).map(|gvas| gvas.write());
+}
+
+#[test]
+fn truncated_saves_never_panic() {
+    let buf = common::save_fixture();
+    for len in 0..buf.len() {
+        load(&buf[..len]);
+    }
+}
+
+#[test]
+fn corrupted_saves_never_panic() {
+    let buf = common::save_fixture();
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+
+    for _ in 0..5000 {
+        let mut corrupted = buf.clone();
+        for _ in 0..1 + rng.below(8) {
+            let pos = rng.below(corrupted.len());
+            corrupted[pos] = match rng.below(4) {
+                0 => 0xFF,
+                1 => 0x00,
+                2 => corrupted[pos] ^ (1 << rng.below(8)),
+                _ => rng.next() as u8
+            };
+        }
+        load(&corrupted);
+    }
+}
+
+#[test]
+fn random_buffers_never_panic() {
+    let header = common::header();
+    let mut rng = Rng(0xD1B5_4A32_D192_ED03);
+
+    for i in 0..5000 {
+        let len = rng.below(512);
+        let mut buf: Vec<u8> = if i % 2 == 0 { header.clone() } else { Vec::new() };
+        buf.extend((0..len).map(|_| rng.next() as u8));
+        load(&buf);
+    }
+}
+
+#[test]
+fn deeply_nested_structs_fall_back_to_raw() {
+    let mut property = common::int("XP", 0);
+    for _ in 0..1000 {
+        property = common::struct_property("Nested", "Nested", &[property]);
+    }
+    let buf = common::build_save(&[property]);
+    assert_eq!(Gvas::read(&buf).unwrap().write(), buf);
+}
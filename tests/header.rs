@@ -0,0 +1,64 @@
+mod common;
+
+use deeprockgalactic_saveeditor::deep_rock_galactic::{SaveError, SaveFile, SaveHeader, VersionWarning};
+
+fn header_with(save_game_class: &str, engine_minor: u16) -> Vec<u8> {
+    let mut buf = b"GVAS".to_vec();
+    buf.extend(2u32.to_le_bytes());
+    buf.extend(522u32.to_le_bytes());
+    buf.extend(4u16.to_le_bytes());
+    buf.extend(engine_minor.to_le_bytes());
+    buf.extend(0u16.to_le_bytes());
+    buf.extend(0u32.to_le_bytes());
+    buf.extend(common::string("++UE4+Release-4.28"));
+    buf.extend(3u32.to_le_bytes());
+    buf.extend(0u32.to_le_bytes());
+    buf.extend(common::string(save_game_class));
+    buf
+}
+
+fn with_header(header: Vec<u8>) -> Vec<u8> {
+    let fixture = common::save_fixture();
+    let mut buf = header;
+    buf.extend(&fixture[common::header().len()..]);
+    buf
+}
+
+#[test]
+fn header_is_exposed() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let header = save_file.header();
+
+    assert_eq!(header.save_game_version, 2);
+    assert_eq!(header.package_version, 522);
+    assert_eq!((header.engine_version.major, header.engine_version.minor, header.engine_version.patch), (4, 27, 2));
+    assert_eq!(header.engine_version.branch, "++UE4+Release-4.27");
+    assert_eq!(header.custom_versions.len(), 2);
+    assert_eq!(header.custom_versions[1].version, 7);
+    assert_eq!(header.save_game_class, "/Script/FSD.FSDSaveGame");
+    assert!(save_file.version_warnings().is_empty());
+    assert_eq!(&SaveHeader::read(&buf).unwrap(), header);
+}
+
+#[test]
+fn unknown_engine_version_warns() {
+    let buf = with_header(header_with("/Script/FSD.FSDSaveGame", 28));
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+
+    assert_eq!(save_file.version_warnings(), &[VersionWarning::EngineVersion {
+        major: 4,
+        minor: 28,
+        branch: "++UE4+Release-4.28".to_string()
+    }]);
+}
+
+#[test]
+fn foreign_save_class_is_refused() {
+    let buf = with_header(header_with("/Script/Engine.SaveGame", 27));
+
+    match SaveFile::new(&buf, common::MATRIX_CORES) {
+        Err(SaveError::UnsupportedSave(_)) => {},
+        other => panic!("unexpected result {:?}", other.map(|_| ()))
+    }
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use deeprockgalactic_saveeditor::{deep_rock_galactic::{Assignment, SaveFile}, gvas::Guid};
+
+fn guid(hex: &str) -> Guid {
+    Guid::from_hex(hex).unwrap()
+}
+
+#[test]
+fn milestones_and_assignments_are_loaded() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+
+    assert_eq!(save_file.milestone("EnemiesKilled"), 48_211);
+    assert_eq!(save_file.milestone("DeepDivesCompleted"), 0);
+    assert_eq!(save_file.assignments.active, Some(guid(common::ACTIVE_ASSIGNMENT)));
+    let completed: Vec<_> = save_file.assignments.completed().map(|assignment| assignment.id).collect();
+    assert_eq!(completed, vec![guid(common::COMPLETED_ASSIGNMENT)]);
+    assert_eq!(save_file.assignments.get(&guid(common::ACTIVE_ASSIGNMENT)).unwrap().progress, 2);
+}
+
+#[test]
+fn milestone_and_assignment_edits_are_persisted() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+
+    save_file.milestones.insert("EnemiesKilled".to_string(), 50_000);
+    save_file.milestones.insert("BarrelsKicked".to_string(), 12);
+    save_file.milestones.remove("MineralsMined");
+    save_file.assignments.list[1].completed = true;
+    save_file.assignments.list.push(Assignment { id: guid("5C5C5C5C5C5C5C5C5C5C5C5C5C5C5C5C"), progress: 0, completed: false });
+    save_file.assignments.active = None;
+
+    let written = save_file.to_bytes().unwrap();
+    let reloaded = SaveFile::new(&written, common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.milestones, save_file.milestones);
+    assert_eq!(reloaded.assignments, save_file.assignments);
+    assert!(!written.windows(16).any(|w| w == b"ActiveAssignment"));
+}
+
+#[test]
+fn assignments_without_a_completed_flag_round_trip() {
+    let mut properties = common::fixture_properties();
+    let pos = properties.iter().position(|p| p.windows(14).any(|w| w == b"AssignmentSave")).unwrap();
+    properties[pos] = common::struct_array("Assignments", "AssignmentSave", &[
+        common::struct_body(&[common::guid_property("AssignmentID", &common::guid(common::ACTIVE_ASSIGNMENT)), common::int("Progress", 2)])
+    ]);
+    let buf = common::build_save(&properties);
+
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert!(!save_file.assignments.get(&guid(common::ACTIVE_ASSIGNMENT)).unwrap().completed);
+    assert_eq!(save_file.to_bytes().unwrap(), buf);
+
+    save_file.assignments.list[0].completed = true;
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert!(reloaded.assignments.get(&guid(common::ACTIVE_ASSIGNMENT)).unwrap().completed);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use deeprockgalactic_saveeditor::{deep_rock_galactic::{DwarfClass, PerkCatalog, SaveError, SaveFile, UnlockedPerk}, gvas::Guid};
+
+fn perk(hex: &str) -> Guid {
+    Guid::from_hex(hex).unwrap()
+}
+
+#[test]
+fn perks_are_loaded() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let catalog = PerkCatalog::from_json(common::PERKS).unwrap();
+
+    assert_eq!(save_file.perks.tier(&perk(common::PASSIVE_PERK)), 3);
+    assert_eq!(save_file.perks.tier(&perk(common::LOCKED_PERK)), 0);
+    assert_eq!(save_file.perks.equipped[&DwarfClass::Engineer].active, vec![perk(common::ACTIVE_PERK)]);
+    assert!(save_file.perks.equipped[&DwarfClass::Scout].passive.is_empty());
+    assert_eq!(save_file.perks.points_spent(&catalog).unwrap(), 1 + 2 + 3 + 2);
+    assert!(save_file.perks.invalid_equipped(&catalog).is_empty());
+}
+
+#[test]
+fn perk_edits_are_persisted() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let catalog = PerkCatalog::from_json(common::PERKS).unwrap();
+
+    save_file.perks.unlocked.push(UnlockedPerk { perk: perk(common::LOCKED_PERK), tier: 1 });
+    let gunner = save_file.perks.equipped.get_mut(&DwarfClass::Gunner).unwrap();
+    gunner.active.push(perk(common::LOCKED_PERK));
+    gunner.passive.push(perk(common::ACTIVE_PERK));
+    assert_eq!(save_file.perks.invalid_equipped(&catalog), vec![(DwarfClass::Gunner, perk(common::ACTIVE_PERK))]);
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.perks, save_file.perks);
+}
+
+#[test]
+fn resetting_perks_refunds_points() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let catalog = PerkCatalog::from_json(common::PERKS).unwrap();
+
+    assert_eq!(save_file.reset_perks(&catalog).unwrap(), 8);
+    assert_eq!(save_file.perkpoints, 17 + 8);
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert!(reloaded.perks.unlocked.is_empty());
+    assert!(reloaded.perks.equipped[&DwarfClass::Engineer].active.is_empty());
+    assert_eq!(reloaded.perkpoints, 25);
+}
+
+#[test]
+fn unknown_perks_cant_be_refunded() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let catalog = PerkCatalog::default();
+    assert!(matches!(save_file.reset_perks(&catalog), Err(SaveError::NotInCatalog(_))));
+    assert_eq!(save_file.perkpoints, 17);
+}
+
+#[test]
+fn refunds_past_the_perk_point_range_are_refused() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let mut catalog = PerkCatalog::from_json(common::PERKS).unwrap();
+    catalog.perks.get_mut(common::PASSIVE_PERK).unwrap().tier_costs = vec![u32::MAX, 1];
+    assert!(matches!(save_file.perks.points_spent(&catalog), Err(SaveError::Overflow(_))));
+
+    let catalog = PerkCatalog::from_json(common::PERKS).unwrap();
+    save_file.perkpoints = u32::MAX;
+    let before = save_file.clone();
+    assert!(matches!(save_file.reset_perks(&catalog), Err(SaveError::Overflow(_))));
+    assert_eq!(save_file, before);
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use deeprockgalactic_saveeditor::{deep_rock_galactic::{SaveError, SaveFile}, gvas::Guid};
+
+#[test]
+fn every_owned_resource_is_loaded() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+
+    assert_eq!(save_file.resources.owned.len(), 1);
+    assert_eq!(save_file.resources.owned[&Guid(common::UNNAMED_RESOURCE)], 42.0);
+    assert_eq!(save_file.resource("bismor").unwrap(), 120.0);
+    assert_eq!(save_file.resource("Blank Matrix Core").unwrap(), 4.0);
+    assert!(save_file.resources.named().contains(&(common::UNNAMED_RESOURCE_HEX.to_string(), 42.0)));
+}
+
+#[test]
+fn registered_resources_are_editable_by_name() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+
+    assert!(matches!(save_file.resources.set("Phazyonite", 1.0), Err(SaveError::UnknownResource(_))));
+    save_file.resources.registry
+        .extend_from_json(&format!(r#"{{"{}": "Phazyonite"}}"#, common::UNNAMED_RESOURCE_HEX))
+        .unwrap();
+    save_file.resources.set("Phazyonite", 500.0).unwrap();
+
+    let written = save_file.to_bytes().unwrap();
+    let reloaded = SaveFile::new(&written, common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.resources.owned[&Guid(common::UNNAMED_RESOURCE)], 500.0);
+}
+
+#[test]
+fn typed_resources_are_edited_through_their_fields() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert!(matches!(save_file.resources.set("Croppa", 1.0), Err(SaveError::TypedResource(_))));
+    assert!(matches!(save_file.resources.get("Croppa"), Err(SaveError::TypedResource(_))));
+
+    save_file.set_resource("Bismor", 999.0).unwrap();
+    assert_eq!(save_file.minerals.bismor, 999.0);
+    save_file.minerals.croppa = 2.0;
+    assert_eq!(save_file.resource("Croppa").unwrap(), 2.0);
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.minerals.bismor, 999.0);
+    assert_eq!(reloaded.resource("Bismor").unwrap(), 999.0);
+    assert_eq!(reloaded.minerals.croppa, 2.0);
+}
+
+#[test]
+fn absent_resources_load_as_zero_and_are_inserted() {
+    let resources: Vec<_> = common::resources().into_iter()
+        .filter(|(guid, _)| *guid != common::JADIZ && *guid != common::MALT)
+        .collect();
+    let buf = common::build_save(&common::fixture_properties_with_resources(&resources));
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert_eq!(save_file.minerals.jadiz, 0.0);
+    assert_eq!(save_file.brewing.malt, 0.0);
+    assert_eq!(save_file.resource("Jadiz").unwrap(), 0.0);
+    assert_eq!(save_file.to_bytes().unwrap(), buf);
+
+    save_file.minerals.jadiz = 50.0;
+    save_file.set_resource("Malt Star", 3.0).unwrap();
+
+    let written = save_file.to_bytes().unwrap();
+    let reloaded = SaveFile::new(&written, common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.minerals.jadiz, 50.0);
+    assert_eq!(reloaded.brewing.malt, 3.0);
+    assert_eq!(reloaded.minerals.bismor, 120.0);
+    assert_eq!(reloaded.resource("Malt Star").unwrap(), 3.0);
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use std::{env, fs::{self, File}, io::Cursor};
+
+use deeprockgalactic_saveeditor::{deep_rock_galactic::{OverclockState, SaveFile}, gvas::{self, Gvas}};
+
+#[test]
+fn gvas_round_trip_is_byte_identical() {
+    let buf = common::save_fixture();
+    let gvas = Gvas::read(&buf).unwrap();
+    assert_eq!(gvas.write(), buf);
+}
+
+// An empty string that kept its terminator, and ASCII stored as UTF-16
+fn unusual_strings() -> Vec<Vec<u8>> {
+    let mut terminated_empty = 1i32.to_le_bytes().to_vec();
+    terminated_empty.push(0);
+    let mut wide_ascii = (-4i32).to_le_bytes().to_vec();
+    for unit in "abc".encode_utf16().chain([0]) {
+        wide_ascii.extend(unit.to_le_bytes());
+    }
+    vec![
+        common::property("EmptyName", "StrProperty", &[0], &terminated_empty),
+        common::property("WideName", "StrProperty", &[0], &wide_ascii),
+        common::struct_property("Nested", "NestedSave", &[common::property("WideName", "StrProperty", &[0], &wide_ascii)])
+    ]
+}
+
+#[test]
+fn unusual_string_encodings_round_trip() {
+    let mut properties = common::fixture_properties();
+    properties.extend(unusual_strings());
+    let buf = common::build_save(&properties);
+
+    let gvas = Gvas::read(&buf).unwrap();
+    assert_eq!(gvas::find_property(&gvas.properties, "EmptyName").unwrap().value, gvas::PropertyValue::Str(String::new()));
+    assert_eq!(gvas::find_property(&gvas.properties, "WideName").unwrap().value, gvas::PropertyValue::Str("abc".to_string()));
+    assert_eq!(gvas.write(), buf);
+
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert_eq!(save_file.to_bytes().unwrap(), buf);
+
+    save_file.credits = 1;
+    let written = save_file.to_bytes().unwrap();
+    assert_eq!(Gvas::read(&written).unwrap().properties.last(), gvas.properties.last());
+    assert!(written.windows(8).any(|w| w == [b'a', 0, b'b', 0, b'c', 0, 0, 0]));
+}
+
+#[test]
+fn shallower_properties_are_found_before_nested_ones() {
+    let buf = common::build_save(&[
+        common::struct_property("Outer", "OuterSave", &[
+            common::struct_property("Inner", "InnerSave", &[common::int("Credits", 1)])
+        ]),
+        common::struct_property("Wallet", "WalletSave", &[common::int("Credits", 2)])
+    ]);
+    let mut gvas = Gvas::read(&buf).unwrap();
+
+    assert_eq!(gvas::find_property(&gvas.properties, "Credits").unwrap().value.as_int(), Some(2));
+    *gvas::find_property_mut(&mut gvas.properties, "Credits").unwrap().value.as_int_mut().unwrap() = 3;
+    let wallet = gvas::find_property_list(&gvas.properties, &|list| list.iter().any(|p| p.name == "Credits")).unwrap();
+    assert_eq!(wallet[0].value.as_int(), Some(3));
+}
+
+#[test]
+fn unmodified_save_round_trip_is_byte_identical() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert_eq!(save_file.to_bytes().unwrap(), buf);
+}
+
+#[test]
+fn save_without_perk_points_round_trips() {
+    let properties: Vec<Vec<u8>> = common::fixture_properties().into_iter()
+        .filter(|p| !p.windows(10).any(|w| w == b"PerkPoints"))
+        .collect();
+    let buf = common::build_save(&properties);
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert_eq!(save_file.perkpoints, 0);
+    assert_eq!(save_file.to_bytes().unwrap(), buf);
+
+    save_file.perkpoints = 5;
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.perkpoints, 5);
+}
+
+#[test]
+fn modified_save_recomputes_sizes() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+
+    save_file.credits = 1_000_000;
+    save_file.minerals.croppa = 999.0;
+    let unforged = [
+        "C3060324CE482C4AB6DC44ED498CBA39",
+        "7306221F1264B04B8C025DB4DE9D1667"
+    ];
+    for uuid in &unforged {
+        save_file.matrix_cores.overclocks.get_mut(*uuid).unwrap().state = OverclockState::Unforged;
+    }
+
+    let written = save_file.to_bytes().unwrap();
+    assert!(Gvas::read(&written).is_ok());
+
+    let reloaded = SaveFile::new(&written, common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.credits, 1_000_000);
+    assert_eq!(reloaded.minerals.croppa, 999.0);
+    assert_eq!(reloaded.minerals.bismor, 120.0);
+    for uuid in unforged.iter().chain(&[common::UNFORGED_OVERCLOCK]) {
+        assert_eq!(reloaded.matrix_cores.overclocks[*uuid].state, OverclockState::Unforged);
+    }
+    assert_eq!(reloaded.matrix_cores.overclocks[common::FORGED_OVERCLOCK].state, OverclockState::Forged);
+}
+
+#[test]
+fn clearing_unforged_removes_owned_schematics() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    save_file.matrix_cores.overclocks.get_mut(common::UNFORGED_OVERCLOCK).unwrap().state = OverclockState::Unacquired;
+
+    let written = save_file.to_bytes().unwrap();
+    let reloaded = SaveFile::new(&written, common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.matrix_cores.overclocks[common::UNFORGED_OVERCLOCK].state, OverclockState::Unacquired);
+    assert!(!written.windows(15).any(|w| w == b"OwnedSchematics"));
+}
+
+#[test]
+fn streams_round_trip() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::from_reader(Cursor::new(&buf), common::MATRIX_CORES).unwrap();
+
+    let mut written = Vec::new();
+    save_file.to_writer(&mut written).unwrap();
+    assert_eq!(written, buf);
+
+    let path = env::temp_dir().join(format!("drg-saveeditor-{}-streams.sav", std::process::id()));
+    save_file.save(&File::create(&path).unwrap()).unwrap();
+    let reloaded = SaveFile::from_path(&path, common::MATRIX_CORES).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(reloaded, save_file);
+}
+
+#[test]
+fn writing_does_not_change_the_loaded_save() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+
+    save_file.credits = 1;
+    save_file.to_bytes().unwrap();
+    save_file.credits = 123_456;
+    assert_eq!(save_file.to_bytes().unwrap(), buf);
+}
+
+#[test]
+fn forged_schematics_follow_matrix_core_state() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let newly_forged = "C3060324CE482C4AB6DC44ED498CBA39";
+    save_file.matrix_cores.overclocks.get_mut(common::UNFORGED_OVERCLOCK).unwrap().state = OverclockState::Forged;
+    save_file.matrix_cores.overclocks.get_mut(newly_forged).unwrap().state = OverclockState::Forged;
+    save_file.matrix_cores.cosmetics.get_mut(common::FORGED_COSMETIC).unwrap().state = OverclockState::Unacquired;
+
+    let written = save_file.to_bytes().unwrap();
+    let reloaded = SaveFile::new(&written, common::MATRIX_CORES).unwrap();
+    for uuid in &[common::FORGED_OVERCLOCK, common::UNFORGED_OVERCLOCK, newly_forged] {
+        assert_eq!(reloaded.matrix_cores.overclocks[*uuid].state, OverclockState::Forged);
+    }
+    assert_eq!(reloaded.matrix_cores.cosmetics[common::FORGED_COSMETIC].state, OverclockState::Unacquired);
+    assert!(!written.windows(15).any(|w| w == b"OwnedSchematics"));
+}
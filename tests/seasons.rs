@@ -0,0 +1,53 @@
+mod common;
+
+use deeprockgalactic_saveeditor::deep_rock_galactic::{MAX_SEASON_LEVEL, SaveError, SaveFile};
+
+#[test]
+fn season_progress_is_loaded() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+
+    assert_eq!(save_file.scrip, 9);
+    let season = save_file.season(4).unwrap();
+    assert_eq!((season.xp, season.level()), (62_500, 13));
+    assert_eq!(season.claimed_nodes, vec![0, 3, 7]);
+    assert!(season.is_claimed(3));
+    assert!(save_file.season(5).is_none());
+}
+
+#[test]
+fn season_edits_are_persisted() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+
+    save_file.scrip = 250;
+    save_file.season_mut(4).set_level(MAX_SEASON_LEVEL).unwrap();
+    save_file.season_mut(4).claim(12);
+    save_file.season_mut(5).xp = 4_999;
+    assert!(matches!(save_file.season_mut(5).set_level(101), Err(SaveError::InvalidLevel(101))));
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.scrip, 250);
+    assert_eq!(reloaded.seasons, save_file.seasons);
+    assert_eq!(reloaded.season(4).unwrap().level(), MAX_SEASON_LEVEL);
+    assert_eq!(reloaded.season(5).unwrap().level(), 1);
+}
+
+#[test]
+fn seasons_without_claimed_nodes_round_trip() {
+    let mut properties = common::fixture_properties();
+    let pos = properties.iter().position(|p| p.windows(7).any(|w| w == b"Seasons")).unwrap();
+    properties[pos] = common::struct_array("Seasons", "SeasonSave", &[common::struct_body(&[
+        common::int("SeasonID", 4),
+        common::int("SeasonXP", 62_500)
+    ])]);
+    let buf = common::build_save(&properties);
+
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert!(save_file.season(4).unwrap().claimed_nodes.is_empty());
+    assert_eq!(save_file.to_bytes().unwrap(), buf);
+
+    save_file.season_mut(4).claim(2);
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.season(4).unwrap().claimed_nodes, vec![2]);
+}
@@ -0,0 +1,17 @@
+mod common;
+
+use deeprockgalactic_saveeditor::deep_rock_galactic::{DwarfClass, SaveFile};
+
+#[test]
+fn mission_stats_are_read() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let stats = save_file.mission_stats();
+
+    assert_eq!(stats.missions_completed[&DwarfClass::Engineer], 212);
+    assert_eq!(stats.missions_completed[&DwarfClass::Scout], 0);
+    assert_eq!(stats.total_missions_completed(), 212);
+    assert_eq!((stats.deep_dives_completed, stats.elite_deep_dives_completed), (14, 3));
+    assert!(stats.weekly_deep_dive_completed);
+    assert!(!stats.weekly_elite_deep_dive_completed);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use deeprockgalactic_saveeditor::deep_rock_galactic::{Mineral, Minerals, SaveError, SaveFile, Trade, TradeRates};
+
+#[test]
+fn trades_move_minerals_and_credits() {
+    let rates = TradeRates { exchange: 3, buy: 50, sell: 10 };
+    let mut minerals = Minerals { bismor: 100.0, ..Minerals::default() };
+    let mut credits = 1000;
+
+    minerals.trade(&rates, &Trade::Exchange { from: Mineral::Bismor, to: Mineral::Enor, amount: 20 }, &mut credits).unwrap();
+    assert_eq!((minerals.bismor, minerals.enor), (40.0, 20.0));
+    minerals.trade(&rates, &Trade::Buy { mineral: Mineral::Jadiz, amount: 4 }, &mut credits).unwrap();
+    assert_eq!((minerals.jadiz, credits), (4.0, 800));
+    minerals.trade(&rates, &Trade::Sell { mineral: Mineral::Enor, amount: 5 }, &mut credits).unwrap();
+    assert_eq!((minerals.enor, credits), (15.0, 850));
+
+    let before = minerals.clone();
+    assert!(matches!(
+        minerals.trade(&rates, &Trade::Exchange { from: Mineral::Bismor, to: Mineral::Croppa, amount: 14 }, &mut credits),
+        Err(SaveError::InsufficientFunds { .. })
+    ));
+    assert!(matches!(minerals.trade(&rates, &Trade::Buy { mineral: Mineral::Croppa, amount: 100 }, &mut credits), Err(SaveError::InsufficientFunds { .. })));
+    assert!(matches!(
+        minerals.trade(&rates, &Trade::Exchange { from: Mineral::Enor, to: Mineral::Enor, amount: 1 }, &mut credits),
+        Err(SaveError::InvalidTrade(_))
+    ));
+    assert_eq!(minerals, before);
+    assert_eq!(credits, 850);
+}
+
+#[test]
+fn trades_too_large_for_a_u32_are_invalid() {
+    let rates = TradeRates::default();
+    let mut minerals = Minerals { bismor: 100.0, ..Minerals::default() };
+    let mut credits = u32::MAX;
+
+    for trade in &[
+        Trade::Buy { mineral: Mineral::Jadiz, amount: 100_000_000 },
+        Trade::Exchange { from: Mineral::Bismor, to: Mineral::Enor, amount: u32::MAX },
+        Trade::Sell { mineral: Mineral::Bismor, amount: 1 }
+    ] {
+        assert!(matches!(minerals.trade(&rates, trade, &mut credits), Err(SaveError::InvalidTrade(_))));
+    }
+    assert_eq!(minerals, Minerals { bismor: 100.0, ..Minerals::default() });
+    assert_eq!(credits, u32::MAX);
+}
+
+#[test]
+fn trades_to_forge_make_an_overclock_affordable() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let rates = TradeRates::default();
+    let cost = save_file.matrix_cores.overclocks[common::UNFORGED_OVERCLOCK].cost.clone();
+    save_file.minerals = Minerals { bismor: 10_000.0, ..Minerals::default() };
+
+    let trades = save_file.trades_to_forge(common::UNFORGED_OVERCLOCK, &rates).unwrap();
+    assert!(trades.iter().all(|trade| matches!(trade, Trade::Exchange { from: Mineral::Bismor, .. })));
+    for trade in &trades {
+        save_file.trade(&rates, trade).unwrap();
+    }
+    assert_eq!(cost.shortfall(save_file.credits, &save_file.minerals), None);
+    assert!(save_file.trades_to_forge(common::UNFORGED_OVERCLOCK, &rates).unwrap().is_empty());
+    save_file.forge(common::UNFORGED_OVERCLOCK).unwrap();
+}
+
+#[test]
+fn trades_to_forge_sell_minerals_for_missing_credits() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let rates = TradeRates::default();
+    let cost = save_file.matrix_cores.overclocks[common::UNFORGED_OVERCLOCK].cost.clone();
+    save_file.credits = 0;
+    save_file.minerals = Minerals { croppa: 100_000.0, ..Minerals::default() };
+
+    let trades = save_file.trades_to_forge(common::UNFORGED_OVERCLOCK, &rates).unwrap();
+    assert!(trades.iter().any(|trade| matches!(trade, Trade::Sell { mineral: Mineral::Croppa, .. })));
+    for trade in &trades {
+        save_file.trade(&rates, trade).unwrap();
+    }
+    assert_eq!(cost.shortfall(save_file.credits, &save_file.minerals), None);
+
+    save_file.credits = 0;
+    save_file.minerals = Minerals::default();
+    assert!(matches!(save_file.trades_to_forge(common::UNFORGED_OVERCLOCK, &rates), Err(SaveError::InsufficientFunds { .. })));
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use deeprockgalactic_saveeditor::{deep_rock_galactic::{DwarfClass, SaveError, SaveFile, VanityCatalog, VanityKind}, gvas::Guid};
+
+fn guid(hex: &str) -> Guid {
+    Guid::from_hex(hex).unwrap()
+}
+
+#[test]
+fn vanity_ownership_is_loaded() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let catalog = VanityCatalog::from_json(common::VANITY).unwrap();
+
+    assert_eq!(save_file.vanity.owned[&VanityKind::ArmorPaintjob], vec![guid(common::PAINTJOB)]);
+    assert!(save_file.vanity.owned[&VanityKind::Headwear].is_empty());
+    assert!(save_file.vanity.owns(&guid(common::BEARD)));
+    assert_eq!(save_file.vanity.missing(&catalog, VanityKind::VictoryPose), vec![guid(common::VICTORY_POSE)]);
+    assert_eq!(catalog.get(&guid(common::PAINTJOB)).unwrap().class, Some(DwarfClass::Driller));
+}
+
+#[test]
+fn vanity_edits_are_persisted() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let catalog = VanityCatalog::from_json(common::VANITY).unwrap();
+
+    save_file.vanity.unlock(&catalog, guid(common::VICTORY_POSE)).unwrap();
+    save_file.vanity.lock(&guid(common::BEARD));
+    assert!(matches!(save_file.vanity.unlock(&catalog, guid(common::FORGED_COSMETIC)), Err(SaveError::NotInCatalog(_))));
+    save_file.vanity.unlock_kind(VanityKind::Headwear, guid(common::FORGED_COSMETIC));
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.vanity.owned[&VanityKind::VictoryPose], vec![guid(common::VICTORY_POSE)]);
+    assert!(!reloaded.vanity.owns(&guid(common::BEARD)));
+    assert!(reloaded.vanity.owns(&guid(common::PAINTJOB)));
+    assert_eq!(reloaded.vanity.owned[&VanityKind::Headwear], vec![guid(common::FORGED_COSMETIC)]);
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use deeprockgalactic_saveeditor::{deep_rock_galactic::{Maintenance, OverclockState, SaveError, SaveFile, Weapon, WeaponCatalog, WeaponLoadout}, gvas::Guid};
+
+fn guid(hex: &str) -> Guid {
+    Guid::from_hex(hex).unwrap()
+}
+
+#[test]
+fn upgrades_and_loadouts_are_loaded() {
+    let buf = common::save_fixture();
+    let save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let catalog = WeaponCatalog::from_json(common::WEAPONS).unwrap();
+
+    let deepcore = catalog.weapon_id(&save_file.matrix_cores.overclocks[common::FORGED_OVERCLOCK].weapon).unwrap();
+    assert_eq!(deepcore, guid(common::DEEPCORE));
+    let tiers = save_file.weapons.owned_by_tier(&catalog, "Deepcore GK2");
+    assert_eq!(tiers[&1], vec![guid(common::GK2_DAMAGE), guid(common::GK2_AMMO)]);
+    assert!(!tiers.contains_key(&2));
+    assert_eq!(save_file.weapons.loadout(&deepcore, 0).unwrap().selected, vec![guid(common::GK2_DAMAGE)]);
+    assert!(save_file.weapons.invalid_selections(&catalog).is_empty());
+}
+
+#[test]
+fn upgrade_edits_are_persisted() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let catalog = WeaponCatalog::from_json(common::WEAPONS).unwrap();
+    let deepcore = guid(common::DEEPCORE);
+
+    save_file.weapons.owned.push(guid(common::GK2_RELOAD));
+    save_file.weapons.loadout_mut(&deepcore, 0).unwrap().selected = vec![guid(common::GK2_AMMO), guid(common::GK2_RELOAD)];
+    save_file.weapons.loadouts.push(WeaponLoadout { weapon: deepcore, loadout: 1, selected: vec![guid(common::GK2_DAMAGE), guid(common::GK2_AMMO)], overclock: None });
+    assert_eq!(save_file.weapons.invalid_selections(&catalog), vec![(deepcore, 1, guid(common::GK2_AMMO))]);
+
+    let written = save_file.to_bytes().unwrap();
+    let reloaded = SaveFile::new(&written, common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.weapons, save_file.weapons);
+    assert!(written.windows(9).any(|w| w == b"bFavorite"));
+}
+
+#[test]
+fn loadouts_without_a_selection_round_trip() {
+    let mut properties = common::fixture_properties();
+    let pos = properties.iter().position(|p| p.windows(14).any(|w| w == b"WeaponLoadouts")).unwrap();
+    properties[pos] = common::struct_array("WeaponLoadouts", "WeaponLoadout", &[common::struct_body(&[
+        common::guid_property("WeaponID", &common::guid(common::DEEPCORE)),
+        common::int("LoadoutIndex", 0),
+        common::boolean("bFavorite", true)
+    ])]);
+    let buf = common::build_save(&properties);
+
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    assert!(save_file.weapons.loadout(&guid(common::DEEPCORE), 0).unwrap().selected.is_empty());
+    assert_eq!(save_file.to_bytes().unwrap(), buf);
+
+    save_file.weapons.loadout_mut(&guid(common::DEEPCORE), 0).unwrap().selected = vec![guid(common::GK2_AMMO)];
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.weapons.loadout(&guid(common::DEEPCORE), 0).unwrap().selected, vec![guid(common::GK2_AMMO)]);
+}
+
+#[test]
+fn only_forged_overclocks_of_the_weapon_can_be_equipped() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let catalog = WeaponCatalog::from_json(common::WEAPONS).unwrap();
+    let deepcore = guid(common::DEEPCORE);
+    assert_eq!(save_file.weapons.loadout(&deepcore, 0).unwrap().overclock, None);
+
+    let unforged = save_file.equip_overclock(&catalog, &deepcore, 0, Some(guid(common::UNFORGED_OVERCLOCK)));
+    assert!(matches!(unforged, Err(SaveError::CannotEquip { .. })));
+    let other_weapon = save_file.matrix_cores.overclocks.iter().find(|(_, oc)| oc.weapon != "Deepcore GK2").map(|(uuid, _)| guid(uuid)).unwrap();
+    save_file.matrix_cores.overclocks.get_mut(&other_weapon.to_hex()).unwrap().state = OverclockState::Forged;
+    assert!(matches!(save_file.equip_overclock(&catalog, &deepcore, 0, Some(other_weapon)), Err(SaveError::CannotEquip { .. })));
+
+    save_file.equip_overclock(&catalog, &deepcore, 0, Some(guid(common::FORGED_OVERCLOCK))).unwrap();
+    save_file.equip_overclock(&catalog, &deepcore, 2, Some(guid(common::FORGED_OVERCLOCK))).unwrap();
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.weapons.loadout(&deepcore, 0).unwrap().overclock, Some(guid(common::FORGED_OVERCLOCK)));
+    assert_eq!(reloaded.weapons.loadout(&deepcore, 2).unwrap().overclock, Some(guid(common::FORGED_OVERCLOCK)));
+    assert!(reloaded.invalid_equipped_overclocks(&catalog).is_empty());
+
+    save_file.equip_overclock(&catalog, &deepcore, 0, None).unwrap();
+    save_file.matrix_cores.overclocks.get_mut(common::FORGED_OVERCLOCK).unwrap().state = OverclockState::Unforged;
+    assert_eq!(save_file.invalid_equipped_overclocks(&catalog), vec![(deepcore, 2, guid(common::FORGED_OVERCLOCK))]);
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.weapons.loadout(&deepcore, 0).unwrap().overclock, None);
+}
+
+#[test]
+fn weapon_maintenance_is_editable_by_weapon_name() {
+    let buf = common::save_fixture();
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let mut catalog = WeaponCatalog::from_json(common::WEAPONS).unwrap();
+    let weapon = save_file.matrix_cores.overclocks[common::FORGED_OVERCLOCK].weapon.clone();
+    assert_eq!(save_file.maintenance(&catalog, &weapon).unwrap(), Maintenance { level: 2, xp: 1500 });
+    assert!(matches!(save_file.maintenance(&catalog, "Zhukov NUK17"), Err(SaveError::UnknownWeapon(_))));
+
+    let zhukov = save_file.matrix_cores.overclocks.values().find(|oc| oc.weapon == "Zhukov NUK17").unwrap().class;
+    catalog.weapons.insert("4E4E4E4E4E4E4E4E4E4E4E4E4E4E4E4E".to_string(), Weapon { class: zhukov, name: "Zhukov NUK17".to_string() });
+    save_file.set_maintenance(&catalog, &weapon, 5, 0).unwrap();
+    save_file.set_maintenance(&catalog, "Zhukov NUK17", 1, 250).unwrap();
+
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.maintenance(&catalog, &weapon).unwrap(), Maintenance { level: 5, xp: 0 });
+    assert_eq!(reloaded.maintenance(&catalog, "Zhukov NUK17").unwrap(), Maintenance { level: 1, xp: 250 });
+    assert_eq!(reloaded.weapons.maintenance.len(), 2);
+}
+
+#[test]
+fn maintenance_is_saved_without_loadouts() {
+    let properties: Vec<Vec<u8>> = common::fixture_properties().into_iter()
+        .filter(|p| !p.windows(14).any(|w| w == b"WeaponLoadouts" || w == b"WeaponMaintena"))
+        .collect();
+    let buf = common::build_save(&properties);
+    let mut save_file = SaveFile::new(&buf, common::MATRIX_CORES).unwrap();
+    let catalog = WeaponCatalog::from_json(common::WEAPONS).unwrap();
+    assert!(save_file.weapons.loadouts.is_empty());
+    assert_eq!(save_file.to_bytes().unwrap(), buf);
+
+    save_file.set_maintenance(&catalog, "Deepcore GK2", 5, 900).unwrap();
+    let reloaded = SaveFile::new(&save_file.to_bytes().unwrap(), common::MATRIX_CORES).unwrap();
+    assert_eq!(reloaded.maintenance(&catalog, "Deepcore GK2").unwrap(), Maintenance { level: 5, xp: 900 });
+    assert!(reloaded.weapons.loadouts.is_empty());
+}